@@ -0,0 +1,54 @@
+#[macro_use] extern crate criterion;
+extern crate boggle;
+extern crate rand;
+extern crate sequence_trie;
+
+#[path = "../tests/naive/mod.rs"]
+mod naive;
+
+use boggle::{Dict, Solver};
+use criterion::{Criterion, BenchmarkId};
+use naive::naive;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+const SIZES: &[usize] = &[4, 5, 6];
+
+/// Letters weighted roughly like the dice of the game
+const LETTERS: &[u8] = b"eeeeeeeeettttttaaaaaarrrrrriiiiiinnnnnoooooossssdddcchhllffmmppuuggywbjkqvxz";
+
+fn board(size: usize, seed: u64) -> Vec<char> {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    (0..size * size)
+        .map(|_| LETTERS[rng.gen_range(0, LETTERS.len())] as char)
+        .collect()
+}
+
+fn solve(c: &mut Criterion) {
+    let dict = include_str!("../dict/dict").split_whitespace().collect::<Dict>();
+    let mut group = c.benchmark_group("solve");
+
+    for &size in SIZES {
+        let cells = board(size, size as u64);
+        let solver = Solver::new(size, size);
+
+        group.bench_with_input(BenchmarkId::new("naive", size), &cells, |b, cells| {
+            b.iter(|| naive(size, size, cells, &dict))
+        });
+
+        group.bench_with_input(BenchmarkId::new("solver", size), &cells, |b, cells| {
+            b.iter(|| solver.solve(cells, &dict))
+        });
+
+        #[cfg(feature = "parallel")]
+        group.bench_with_input(BenchmarkId::new("parallel", size), &cells, |b, cells| {
+            b.iter(|| solver.solve_parallel(cells, &dict))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, solve);
+criterion_main!(benches);
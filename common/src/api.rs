@@ -0,0 +1,106 @@
+//! Responses of the read-only HTTP API, served as JSON under `/api`.
+//!
+//! See `API.md` for the endpoints.
+
+use boggle::Grid;
+use chrono::{DateTime, Utc};
+use crate::client::message::RoundResults;
+
+/// `GET /api/health`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Health {
+    /// `"ok"`, or `"unavailable"` if the game doesn't respond
+    pub status: String,
+    pub protocol_version: u32,
+    /// Seconds since the server started
+    pub uptime: u64,
+}
+
+/// A game and who is in it, `GET /api/rooms`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Room {
+    pub id: String,
+    pub grid: Grid,
+    pub dictionary: String,
+    pub started: DateTime<Utc>,
+    pub deadline: DateTime<Utc>,
+    /// Sorted by score, descending
+    pub players: Vec<Score>,
+    pub spectators: usize,
+}
+
+/// Score of a player in the current round, `GET /api/scores`.
+/// The words themselves are only revealed once the round ends.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Score {
+    pub nick: String,
+    pub bot: bool,
+    /// Number of words found
+    pub words: usize,
+    pub score: u32,
+}
+
+/// A round that ended, `GET /api/history`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+    pub started: DateTime<Utc>,
+    pub ended: DateTime<Utc>,
+    pub dictionary: String,
+    pub results: RoundResults,
+}
+
+/// Totals of a player over the kept rounds, `GET /api/leaderboard`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LeaderboardEntry {
+    pub nick: String,
+    pub rounds: usize,
+    /// Rounds with the highest score, ties count for everybody
+    pub wins: usize,
+    pub total_score: u32,
+    pub best_score: u32,
+}
+
+/// Rank players by total score, then by wins, over `rounds`.
+pub fn leaderboard<'a, I>(rounds: I) -> Vec<LeaderboardEntry>
+where
+    I: IntoIterator<Item = &'a Round>,
+{
+    let mut entries: Vec<LeaderboardEntry> = Vec::new();
+
+    for round in rounds {
+        let players = &round.results.players;
+        let top = players.iter().map(|player| player.score).max().unwrap_or(0);
+
+        for player in players {
+            let index = match entries.iter().position(|entry| entry.nick == player.nick) {
+                Some(index) => index,
+                None => {
+                    entries.push(LeaderboardEntry {
+                        nick: player.nick.clone(),
+                        rounds: 0,
+                        wins: 0,
+                        total_score: 0,
+                        best_score: 0,
+                    });
+                    entries.len() - 1
+                },
+            };
+            let entry = &mut entries[index];
+
+            entry.rounds += 1;
+            entry.total_score += player.score;
+            entry.best_score = entry.best_score.max(player.score);
+
+            if player.score == top && top > 0 {
+                entry.wins += 1;
+            }
+        }
+    }
+
+    entries.sort_by(|a, b| {
+        b.total_score.cmp(&a.total_score)
+            .then_with(|| b.wins.cmp(&a.wins))
+            .then_with(|| a.nick.cmp(&b.nick))
+    });
+    entries
+}
@@ -1,15 +1,31 @@
 use failure::Error;
-use boggle::{Grid, Dict};
+use crate::Codec;
+use boggle::{Grid, PackedDict};
 use std::io::{Read, Write};
 use chrono::{DateTime, Utc};
 
+/// Messages sent from the server to clients.
+///
+/// Variants are encoded by their index, so new ones must only be appended.
 #[derive(Serialize, Deserialize, Debug)]
 pub enum Message {
     NewGame(NewGame),
     NickAlreadyInUse(NickAlreadyInUse),
-    PlayerStatus(PlayerStatus)
+    PlayerStatus(PlayerStatus),
+    Dictionaries(Dictionaries),
+    Definition(Definition),
+    Welcome(Welcome),
+    Incompatible(Incompatible),
+    /// A request failed. If the code is fatal, the server closes the connection afterwards.
+    Error { code: ErrorCode, message: String },
+    Chat(Chat),
+    RoundResults(RoundResults),
+    /// The server is going down, the connection will be closed
+    Shutdown(Shutdown),
+    Session(Session),
 }
 
+/// `encode`, `decode`, `from_slice` and `to_vec` use `Codec::Bincode`.
 impl Message {
     pub fn decode<R: Read>(r: &mut R) -> Result<Self, Error> {
         let m = bincode::deserialize_from(r)?;
@@ -30,16 +46,27 @@ impl Message {
         self.encode(&mut data)?;
         Ok(data)
     }
+
+    pub fn from_slice_with(codec: Codec, data: &[u8]) -> Result<Self, Error> {
+        codec.decode(data)
+    }
+
+    pub fn to_vec_with(&self, codec: Codec) -> Result<Vec<u8>, Error> {
+        codec.encode(self)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct NewGame {
+    /// Empty for spectators
     pub nick: String,
     pub grid: Grid,
-    pub words: Dict,
+    pub words: PackedDict,
     pub deadline: DateTime<Utc>,
+    pub dictionary: String,
 }
 
+/// No longer sent, see `ErrorCode::NickTaken`.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct NickAlreadyInUse {
     pub nick: String,
@@ -50,3 +77,130 @@ pub enum PlayerStatus {
     FoundWords { nick: String, count: usize },
     Disconnected { nick: String },
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Dictionaries {
+    pub dictionaries: Vec<DictionaryInfo>,
+    /// Id of the dictionary that will be used for the next game
+    pub selected: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DictionaryInfo {
+    pub id: String,
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Definition {
+    pub word: String,
+    /// `None` if no definition is known for the word, or it wasn't on the grid of the last ended round
+    pub definition: Option<String>,
+}
+
+/// Reply to a compatible `Hello`, after which the client may log in.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Welcome {
+    pub protocol_version: u32,
+    pub capabilities: Vec<String>,
+}
+
+/// Reply to a `Hello` with an unsupported version, or to a missing `Hello`.
+/// The server closes the connection afterwards.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Incompatible {
+    pub protocol_version: u32,
+    pub min_protocol_version: u32,
+    pub message: String,
+}
+
+/// A chat message of a player, including the sender's own ones.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Chat {
+    pub nick: String,
+    pub text: String,
+}
+
+/// What every player found in the round that just ended.
+/// Sent to spectators if the server reveals results.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RoundResults {
+    pub grid: Grid,
+    /// Sorted by score, descending
+    pub players: Vec<PlayerResult>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PlayerResult {
+    pub nick: String,
+    /// Sorted alphabetically
+    pub words: Vec<String>,
+    pub score: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Shutdown {
+    pub message: String,
+}
+
+/// Sent after logging in or resuming, allows resuming the game on another connection.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub token: String,
+    /// Words found in the current game, sorted alphabetically
+    pub words: Vec<String>,
+}
+
+/// Why a request failed, sent along with a human readable message.
+///
+/// Codes are encoded by their index, so new ones must only be appended.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// Something went wrong on the server
+    Internal,
+    /// A message could not be decoded
+    MalformedMessage,
+    /// A message broke the protocol, e.g. a repeated `Hello`
+    UnexpectedMessage,
+    /// `Login` with an empty nick
+    EmptyNick,
+    /// `Login` with a nick somebody else is using
+    NickTaken,
+    /// `SelectDictionary` with an id the server doesn't know
+    UnknownDictionary,
+    /// The client sent too many messages
+    RateLimited,
+    /// The server doesn't accept more players
+    RoomFull,
+    /// Chat is disabled while a game is being played
+    ChatDisabled,
+    /// The chat message exceeds `crate::MAX_CHAT_LENGTH`
+    ChatTooLong,
+    /// `Resume` with a token of a session that ended
+    UnknownSession,
+    /// The client kept exceeding rate limits and may not reconnect for a while
+    Banned,
+    /// The client may not do this in its role, e.g. a spectator submitting a word
+    NotAllowed,
+}
+
+impl ErrorCode {
+    /// Whether the server closes the connection after sending this error.
+    pub fn is_fatal(self) -> bool {
+        match self {
+            ErrorCode::Internal
+            | ErrorCode::MalformedMessage
+            | ErrorCode::UnexpectedMessage
+            | ErrorCode::RoomFull
+            | ErrorCode::Banned => true,
+            ErrorCode::EmptyNick
+            | ErrorCode::NickTaken
+            | ErrorCode::UnknownDictionary
+            | ErrorCode::RateLimited
+            | ErrorCode::ChatDisabled
+            | ErrorCode::ChatTooLong
+            | ErrorCode::UnknownSession
+            | ErrorCode::NotAllowed => false,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use failure::Error;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Encoding of messages on the wire.
+///
+/// Connections pick a codec by the type of their websocket frames:
+/// binary frames carry `Bincode`, text frames carry `Json`.
+/// The JSON schema of every message is documented in `PROTOCOL.md`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Codec {
+    /// Compact binary encoding, used by the bundled clients
+    Bincode,
+    /// Human readable encoding, for third party clients and debugging
+    Json,
+}
+
+impl Codec {
+    /// Codec of a frame, depending on whether it is a text frame.
+    pub fn for_frame(is_text: bool) -> Self {
+        if is_text { Codec::Json } else { Codec::Bincode }
+    }
+
+    /// Whether encoded messages are valid UTF-8 and sent as text frames.
+    pub fn is_text(self) -> bool {
+        self == Codec::Json
+    }
+
+    pub fn encode<T: Serialize>(self, msg: &T) -> Result<Vec<u8>, Error> {
+        let data = match self {
+            Codec::Bincode => bincode::serialize(msg)?,
+            Codec::Json => serde_json::to_vec(msg)?,
+        };
+
+        Ok(data)
+    }
+
+    pub fn decode<T: DeserializeOwned>(self, data: &[u8]) -> Result<T, Error> {
+        let msg = match self {
+            Codec::Bincode => bincode::deserialize(data)?,
+            Codec::Json => serde_json::from_slice(data)?,
+        };
+
+        Ok(msg)
+    }
+}
+
+impl Default for Codec {
+    fn default() -> Self {
+        Codec::Bincode
+    }
+}
@@ -0,0 +1,77 @@
+//! Blocking websocket client for the tools, behind the `connection` feature.
+
+use crate::{client, server, PROTOCOL_VERSION};
+use failure::{Error, bail, format_err};
+use std::io;
+use std::net::TcpStream;
+use std::time::Duration;
+use tungstenite::{self, Message, WebSocket};
+
+/// Websocket connection to a `boggle_server`.
+pub struct Connection {
+    socket: WebSocket<TcpStream>,
+}
+
+impl Connection {
+    /// Connect to a `ws://host:port/path` url.
+    ///
+    /// Receiving waits at most `read_timeout` for a message.
+    pub fn connect(url: &str, read_timeout: Duration) -> Result<Self, Error> {
+        let addr = url.trim_start_matches("ws://")
+            .split('/')
+            .next()
+            .filter(|addr| !addr.is_empty())
+            .ok_or_else(|| format_err!("Invalid server url '{}'", url))?;
+
+        let stream = TcpStream::connect(addr)?;
+        let (socket, _) = tungstenite::client(url, stream).map_err(|e| format_err!("{}", e))?;
+
+        socket.get_ref().set_read_timeout(Some(read_timeout))?;
+
+        Ok(Self {
+            socket,
+        })
+    }
+
+    /// Exchange protocol versions, failing if the server is incompatible.
+    pub fn handshake(&mut self) -> Result<(), Error> {
+        self.send(&server::Message::Hello(server::message::Hello {
+            protocol_version: PROTOCOL_VERSION,
+            capabilities: Vec::new(),
+        }))?;
+
+        loop {
+            match self.try_recv()? {
+                Some(client::Message::Welcome(_)) => return Ok(()),
+                Some(client::Message::Incompatible(msg)) => bail!("{}", msg.message),
+                Some(msg) => bail!("Unexpected message during handshake: {:?}", msg),
+                None => {},
+            }
+        }
+    }
+
+    pub fn send(&mut self, msg: &server::Message) -> Result<(), Error> {
+        self.socket.write_message(Message::Binary(msg.to_vec()?))?;
+        Ok(())
+    }
+
+    /// Receive the next message if one is available.
+    pub fn try_recv(&mut self) -> Result<Option<client::Message>, Error> {
+        match self.socket.read_message() {
+            Ok(Message::Binary(data)) => Ok(Some(client::Message::from_slice(&data)?)),
+            Ok(Message::Close(_)) => bail!("Server closed the connection"),
+            Ok(_) => Ok(None),
+            Err(tungstenite::Error::Io(ref e)) if is_timeout(e) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn close(&mut self) -> Result<(), Error> {
+        self.socket.close(None)?;
+        Ok(())
+    }
+}
+
+fn is_timeout(e: &io::Error) -> bool {
+    e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut
+}
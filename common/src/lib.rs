@@ -1,5 +1,43 @@
 #[macro_use] extern crate serde_derive;
 
+/// Version of the protocol spoken by this crate.
+///
+/// Bump it whenever the encoding of any message changes.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Oldest protocol version that is still compatible with this one.
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// Optional features announced during the handshake.
+pub mod capability {
+    /// Multiple dictionaries, see `server::Message::ListDictionaries`
+    pub const DICTIONARIES: &str = "dictionaries";
+    /// Word definitions, see `server::Message::Define`
+    pub const DEFINITIONS: &str = "definitions";
+    /// Chat between players, see `server::Message::Chat`
+    pub const CHAT: &str = "chat";
+    /// Continuing a game on a new connection, see `server::Message::Resume`
+    pub const RESUME: &str = "resume";
+}
+
+/// Maximum length of a chat message, in characters.
+pub const MAX_CHAT_LENGTH: usize = 300;
+
+/// Whether a peer speaking protocol `version` can talk to this crate.
+pub fn is_compatible(version: u32) -> bool {
+    version >= MIN_PROTOCOL_VERSION && version <= PROTOCOL_VERSION
+}
+
+mod codec;
+pub use self::codec::Codec;
+
+pub mod replay;
+
+pub mod api;
+
+#[cfg(feature = "connection")]
+pub mod connection;
+
 pub mod client {
     pub mod message;
     pub use self::message::Message;
@@ -0,0 +1,207 @@
+//! Event logs of played rounds.
+//!
+//! Every round is logged to its own file, one JSON encoded `Event` per line.
+//! The first event is `RoundStarted` and, if the round was played to its end,
+//! the last one is `RoundEnded`. See `REPLAY.md` for the format.
+
+use boggle::{Grid, points};
+use chrono::{DateTime, Utc};
+use failure::{Error, format_err};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::io::{BufRead, Write};
+use crate::client::message::PlayerResult;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    RoundStarted {
+        time: DateTime<Utc>,
+        grid: Grid,
+        dictionary: String,
+        deadline: DateTime<Utc>,
+        /// Words of the grid, sorted alphabetically
+        words: Vec<String>,
+    },
+    /// A player joined, or was already playing when the round started.
+    /// Joining again starts over with no words found.
+    Joined {
+        time: DateTime<Utc>,
+        nick: String,
+        bot: bool,
+    },
+    Left {
+        time: DateTime<Utc>,
+        nick: String,
+    },
+    Submitted {
+        time: DateTime<Utc>,
+        nick: String,
+        word: String,
+        verdict: Verdict,
+    },
+    RoundEnded {
+        time: DateTime<Utc>,
+        /// Players at the end of the round, sorted by score, descending
+        results: Vec<PlayerResult>,
+    },
+}
+
+impl Event {
+    pub fn time(&self) -> DateTime<Utc> {
+        match *self {
+            Event::RoundStarted { time, .. }
+            | Event::Joined { time, .. }
+            | Event::Left { time, .. }
+            | Event::Submitted { time, .. }
+            | Event::RoundEnded { time, .. } => time,
+        }
+    }
+
+    /// Write the event as a line of JSON.
+    pub fn write<W: Write>(&self, w: &mut W) -> Result<(), Error> {
+        serde_json::to_writer(&mut *w, self)?;
+        w.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Read all events of a log.
+    pub fn read_all<R: BufRead>(r: R) -> Result<Vec<Event>, Error> {
+        let mut events = Vec::new();
+
+        for (index, line) in r.lines().enumerate() {
+            let line = line?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let event = serde_json::from_str(&line)
+                .map_err(|e| format_err!("line {}: {}", index + 1, e))?;
+            events.push(event);
+        }
+
+        Ok(events)
+    }
+}
+
+/// What the server made of a submitted word.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Accepted,
+    /// Not a word of the grid
+    Invalid,
+    /// The player found the word before
+    AlreadyFound,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Verdict::Accepted => "accepted",
+            Verdict::Invalid => "invalid",
+            Verdict::AlreadyFound => "already found",
+        })
+    }
+}
+
+/// Replays a log to recompute what the server decided.
+#[derive(Debug, Default)]
+pub struct Replay {
+    words: BTreeSet<String>,
+    /// Words found by the players that are currently in the game
+    players: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Replay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply an event, returning the verdict a submission should have gotten.
+    pub fn apply(&mut self, event: &Event) -> Option<Verdict> {
+        match event {
+            Event::RoundStarted { words, .. } => {
+                self.words = words.iter().cloned().collect();
+                self.players.clear();
+                None
+            },
+            Event::Joined { nick, .. } => {
+                self.players.insert(nick.clone(), BTreeSet::new());
+                None
+            },
+            Event::Left { nick, .. } => {
+                self.players.remove(nick);
+                None
+            },
+            Event::Submitted { nick, word, .. } => {
+                let found = self.players.entry(nick.clone()).or_default();
+
+                Some(if !self.words.contains(word) {
+                    Verdict::Invalid
+                } else if !found.insert(word.clone()) {
+                    Verdict::AlreadyFound
+                } else {
+                    Verdict::Accepted
+                })
+            },
+            Event::RoundEnded { .. } => None,
+        }
+    }
+
+    /// Apply an event, describing every way what the server recorded in it
+    /// differs from the recomputed verdict or results.
+    pub fn check(&mut self, event: &Event) -> Vec<String> {
+        let expected = self.apply(event);
+
+        match event {
+            Event::Submitted { nick, word, verdict, .. } if expected != Some(*verdict) => vec![format!(
+                "'{}' of {} was {}, expected {}",
+                word, nick, verdict, expected.map_or_else(|| "nothing".into(), |expected| expected.to_string()),
+            )],
+            Event::RoundEnded { results, .. } => compare(results, &self.results()),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Results of the players currently in the game, sorted like `RoundEnded` results.
+    pub fn results(&self) -> Vec<PlayerResult> {
+        let mut results: Vec<PlayerResult> = self.players.iter()
+            .map(|(nick, words)| PlayerResult {
+                nick: nick.clone(),
+                words: words.iter().cloned().collect(),
+                score: words.iter().map(|word| points(word)).sum(),
+            })
+            .collect();
+
+        results.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.nick.cmp(&b.nick)));
+        results
+    }
+}
+
+/// Differences between the recorded and the recomputed results.
+fn compare(recorded: &[PlayerResult], recomputed: &[PlayerResult]) -> Vec<String> {
+    let mut mismatches = Vec::new();
+
+    for result in recorded {
+        match recomputed.iter().find(|other| other.nick == result.nick) {
+            None => mismatches.push(format!("{} has a result, but wasn't in the game", result.nick)),
+            Some(other) if other.words != result.words => mismatches.push(format!(
+                "{} found {} words, expected {}", result.nick, result.words.len(), other.words.len(),
+            )),
+            Some(other) if other.score != result.score => mismatches.push(format!(
+                "{} scored {}, expected {}", result.nick, result.score, other.score,
+            )),
+            Some(_) => {},
+        }
+    }
+
+    for other in recomputed {
+        if !recorded.iter().any(|result| result.nick == other.nick) {
+            mismatches.push(format!("{} was in the game, but has no result", other.nick));
+        }
+    }
+
+    mismatches
+}
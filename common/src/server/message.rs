@@ -1,12 +1,27 @@
 use failure::Error;
+use crate::Codec;
 use std::io::{Read, Write};
 
+/// Messages sent from clients to the server.
+///
+/// The first message of a connection must be `Hello`.
+/// Variants are encoded by their index, so new ones must only be appended.
 #[derive(Serialize, Deserialize, Debug)]
 pub enum Message {
     Login(Login),
     SubmitWord(SubmitWord),
+    ListDictionaries,
+    SelectDictionary(SelectDictionary),
+    Define(Define),
+    Hello(Hello),
+    Chat(Chat),
+    /// Watch the game without playing, instead of `Login`
+    Spectate,
+    /// Continue playing with the session of an earlier connection, instead of `Login`
+    Resume(Resume),
 }
 
+/// `encode`, `decode`, `from_slice` and `to_vec` use `Codec::Bincode`.
 impl Message {
     pub fn decode<R: Read>(r: &mut R) -> Result<Self, Error> {
         let m = bincode::deserialize_from(r)?;
@@ -27,6 +42,14 @@ impl Message {
         self.encode(&mut data)?;
         Ok(data)
     }
+
+    pub fn from_slice_with(codec: Codec, data: &[u8]) -> Result<Self, Error> {
+        codec.decode(data)
+    }
+
+    pub fn to_vec_with(&self, codec: Codec) -> Result<Vec<u8>, Error> {
+        codec.encode(self)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -38,3 +61,33 @@ pub struct Login {
 pub struct SubmitWord {
     pub word: String,
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SelectDictionary {
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Define {
+    pub word: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Hello {
+    /// `crate::PROTOCOL_VERSION` of the client
+    pub protocol_version: u32,
+    /// Optional features the client supports, see `crate::capability`
+    pub capabilities: Vec<String>,
+}
+
+/// A chat message to everybody in the game.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Chat {
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Resume {
+    /// Token of a `client::message::Session`
+    pub token: String,
+}
@@ -0,0 +1,56 @@
+use boggle::Grid;
+use boggle_common::api::{leaderboard, Round, Score};
+use boggle_common::client::message::{PlayerResult, RoundResults};
+use chrono::{TimeZone, Utc};
+
+fn round(scores: &[(&str, u32)]) -> Round {
+    Round {
+        started: Utc.ymd(2020, 5, 1).and_hms(12, 0, 0),
+        ended: Utc.ymd(2020, 5, 1).and_hms(12, 10, 0),
+        dictionary: "english".into(),
+        results: RoundResults {
+            grid: "abcdefghijklmnop".parse::<Grid>().unwrap(),
+            players: scores.iter()
+                .map(|&(nick, score)| PlayerResult {
+                    nick: nick.into(),
+                    words: Vec::new(),
+                    score,
+                })
+                .collect(),
+        },
+    }
+}
+
+#[test]
+fn leaderboard_totals() {
+    let rounds = vec![
+        round(&[("ann", 10), ("bob", 4)]),
+        round(&[("bob", 7), ("ann", 7), ("cid", 2)]),
+        round(&[("cid", 0)]),
+    ];
+    let entries = leaderboard(&rounds);
+    let totals: Vec<_> = entries.iter()
+        .map(|entry| (&*entry.nick, entry.rounds, entry.wins, entry.total_score, entry.best_score))
+        .collect();
+
+    assert_eq!(totals, vec![
+        ("ann", 2, 2, 17, 10),
+        ("bob", 2, 1, 11, 7),
+        ("cid", 2, 0, 2, 2),
+    ]);
+}
+
+#[test]
+fn score_json() {
+    let score = Score {
+        nick: "ann".into(),
+        bot: false,
+        words: 3,
+        score: 5,
+    };
+
+    assert_eq!(
+        serde_json::to_string(&score).unwrap(),
+        r#"{"nick":"ann","bot":false,"words":3,"score":5}"#,
+    );
+}
@@ -0,0 +1,147 @@
+use boggle::Grid;
+use boggle_common::client::message::PlayerResult;
+use boggle_common::replay::{Event, Replay, Verdict};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+fn at(seconds: i64) -> DateTime<Utc> {
+    Utc.ymd(2020, 5, 1).and_hms(12, 0, 0) + Duration::seconds(seconds)
+}
+
+fn submitted(seconds: i64, nick: &str, word: &str, verdict: Verdict) -> Event {
+    Event::Submitted {
+        time: at(seconds),
+        nick: nick.into(),
+        word: word.into(),
+        verdict,
+    }
+}
+
+fn result(nick: &str, words: &[&str], score: u32) -> PlayerResult {
+    PlayerResult {
+        nick: nick.into(),
+        words: words.iter().map(|&word| word.into()).collect(),
+        score,
+    }
+}
+
+/// A round as the server logs it.
+fn log() -> Vec<Event> {
+    vec![
+        Event::RoundStarted {
+            time: at(0),
+            grid: "teanseatxxxxxxxx".parse::<Grid>().unwrap(),
+            dictionary: "english".into(),
+            deadline: at(600),
+            words: vec!["ease".into(), "sea".into(), "seat".into(), "tea".into(), "teas".into(), "teens".into()],
+        },
+        Event::Joined { time: at(0), nick: "ann".into(), bot: false },
+        Event::Joined { time: at(1), nick: "bob".into(), bot: false },
+        submitted(2, "ann", "tea", Verdict::Accepted),
+        submitted(3, "ann", "tea", Verdict::AlreadyFound),
+        submitted(4, "bob", "teens", Verdict::Accepted),
+        submitted(5, "bob", "tean", Verdict::Invalid),
+        Event::Joined { time: at(6), nick: "cid".into(), bot: true },
+        submitted(7, "cid", "sea", Verdict::Accepted),
+        Event::Left { time: at(8), nick: "cid".into() },
+        submitted(9, "ann", "seat", Verdict::Accepted),
+        Event::RoundEnded {
+            time: at(600),
+            results: vec![
+                result("ann", &["seat", "tea"], 2),
+                result("bob", &["teens"], 2),
+            ],
+        },
+    ]
+}
+
+fn check(events: &[Event]) -> Vec<String> {
+    let mut replay = Replay::new();
+
+    events.iter().flat_map(|event| replay.check(event)).collect()
+}
+
+#[test]
+fn scores_are_recomputed() {
+    let events = log();
+    let mut replay = Replay::new();
+
+    // Everything before `RoundEnded`
+    for event in &events[..events.len() - 1] {
+        replay.apply(event);
+    }
+
+    // Players that left are dropped, ties are sorted by nick
+    assert_eq!(replay.results(), vec![
+        result("ann", &["seat", "tea"], 2),
+        result("bob", &["teens"], 2),
+    ]);
+}
+
+#[test]
+fn verdicts_are_recomputed() {
+    let mut replay = Replay::new();
+    let verdicts: Vec<_> = log().iter().filter_map(|event| replay.apply(event)).collect();
+
+    assert_eq!(verdicts, vec![
+        Verdict::Accepted,
+        Verdict::AlreadyFound,
+        Verdict::Accepted,
+        Verdict::Invalid,
+        Verdict::Accepted,
+        Verdict::Accepted,
+    ]);
+}
+
+#[test]
+fn matching_log() {
+    assert_eq!(check(&log()), Vec::<String>::new());
+}
+
+#[test]
+fn tampered_verdict() {
+    let mut events = log();
+    events[4] = submitted(3, "ann", "tea", Verdict::Accepted);
+
+    assert_eq!(check(&events), vec!["'tea' of ann was accepted, expected already found"]);
+}
+
+#[test]
+fn tampered_results() {
+    let mut events = log();
+    events[11] = Event::RoundEnded {
+        time: at(600),
+        results: vec![
+            result("ann", &["seat", "tea"], 11),
+            result("bob", &["teens", "teas"], 3),
+            result("cid", &["sea"], 1),
+        ],
+    };
+
+    assert_eq!(check(&events), vec![
+        "ann scored 11, expected 2",
+        "bob found 2 words, expected 1",
+        "cid has a result, but wasn't in the game",
+    ]);
+}
+
+#[test]
+fn missing_result() {
+    let mut events = log();
+    events[11] = Event::RoundEnded {
+        time: at(600),
+        results: vec![result("bob", &["teens"], 2)],
+    };
+
+    assert_eq!(check(&events), vec!["ann was in the game, but has no result"]);
+}
+
+#[test]
+fn log_round_trip() {
+    let mut data = Vec::new();
+
+    for event in log() {
+        event.write(&mut data).unwrap();
+    }
+
+    assert_eq!(Event::read_all(&data[..]).unwrap(), log());
+}
@@ -0,0 +1,437 @@
+//! Pins the wire encoding of every message.
+//!
+//! If one of these tests fails, old peers can no longer decode the message:
+//! restore the encoding, or bump `PROTOCOL_VERSION` and update the test.
+
+use boggle::{Dict, Grid, PackedDict};
+use boggle_common::{client, server, Codec, PROTOCOL_VERSION};
+use chrono::{TimeZone, Utc};
+
+/// Builds the expected encoding: integers are little endian,
+/// variants are `u32` indices and lengths are `u64`.
+#[derive(Default)]
+struct Wire(Vec<u8>);
+
+impl Wire {
+    fn variant(mut self, index: u32) -> Self {
+        self.0.extend_from_slice(&index.to_le_bytes());
+        self
+    }
+
+    fn u8(mut self, value: u8) -> Self {
+        self.0.push(value);
+        self
+    }
+
+    fn u32(mut self, value: u32) -> Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn u64(mut self, value: u64) -> Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn str(self, value: &str) -> Self {
+        self.bytes(value.as_bytes())
+    }
+
+    fn bytes(mut self, value: &[u8]) -> Self {
+        self = self.u64(value.len() as u64);
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    /// Fixed size arrays have no length prefix
+    fn chars(mut self, value: &str) -> Self {
+        self.0.extend_from_slice(value.as_bytes());
+        self
+    }
+}
+
+fn wire() -> Wire {
+    Wire::default()
+}
+
+fn assert_server(msg: server::Message, expected: Wire) {
+    let encoded = msg.to_vec().unwrap();
+    assert_eq!(encoded, expected.0, "encoding of {:?}", msg);
+
+    let decoded = server::Message::from_slice(&encoded).unwrap();
+    assert_eq!(decoded.to_vec().unwrap(), encoded, "decoding of {:?}", msg);
+}
+
+fn assert_client(msg: client::Message, expected: Wire) {
+    let encoded = msg.to_vec().unwrap();
+    assert_eq!(encoded, expected.0, "encoding of {:?}", msg);
+
+    let decoded = client::Message::from_slice(&encoded).unwrap();
+    assert_eq!(decoded.to_vec().unwrap(), encoded, "decoding of {:?}", msg);
+}
+
+/// Checks the JSON encoding documented in `PROTOCOL.md`.
+fn assert_server_json(msg: server::Message, expected: &str) {
+    let encoded = msg.to_vec_with(Codec::Json).unwrap();
+    assert_eq!(String::from_utf8(encoded.clone()).unwrap(), expected);
+
+    let decoded = server::Message::from_slice_with(Codec::Json, &encoded).unwrap();
+    assert_eq!(decoded.to_vec_with(Codec::Json).unwrap(), encoded, "decoding of {:?}", msg);
+}
+
+fn assert_client_json(msg: client::Message, expected: &str) {
+    let encoded = msg.to_vec_with(Codec::Json).unwrap();
+    assert_eq!(String::from_utf8(encoded.clone()).unwrap(), expected);
+
+    let decoded = client::Message::from_slice_with(Codec::Json, &encoded).unwrap();
+    assert_eq!(decoded.to_vec_with(Codec::Json).unwrap(), encoded, "decoding of {:?}", msg);
+}
+
+#[test]
+fn protocol_version() {
+    assert_eq!(PROTOCOL_VERSION, 1);
+}
+
+#[test]
+fn server_login() {
+    assert_server(
+        server::Message::Login(server::message::Login { nick: "ann".into() }),
+        wire().variant(0).str("ann"),
+    );
+}
+
+#[test]
+fn server_submit_word() {
+    assert_server(
+        server::Message::SubmitWord(server::message::SubmitWord { word: "tea".into() }),
+        wire().variant(1).str("tea"),
+    );
+}
+
+#[test]
+fn server_list_dictionaries() {
+    assert_server(
+        server::Message::ListDictionaries,
+        wire().variant(2),
+    );
+}
+
+#[test]
+fn server_select_dictionary() {
+    assert_server(
+        server::Message::SelectDictionary(server::message::SelectDictionary { id: "kids".into() }),
+        wire().variant(3).str("kids"),
+    );
+}
+
+#[test]
+fn server_define() {
+    assert_server(
+        server::Message::Define(server::message::Define { word: "aalii".into() }),
+        wire().variant(4).str("aalii"),
+    );
+}
+
+#[test]
+fn server_hello() {
+    assert_server(
+        server::Message::Hello(server::message::Hello {
+            protocol_version: 1,
+            capabilities: vec!["definitions".into()],
+        }),
+        wire().variant(5).u32(1).u64(1).str("definitions"),
+    );
+}
+
+#[test]
+fn server_chat() {
+    assert_server(
+        server::Message::Chat(server::message::Chat { text: "hi".into() }),
+        wire().variant(6).str("hi"),
+    );
+}
+
+#[test]
+fn server_spectate() {
+    assert_server(
+        server::Message::Spectate,
+        wire().variant(7),
+    );
+}
+
+#[test]
+fn server_resume() {
+    assert_server(
+        server::Message::Resume(server::message::Resume { token: "abc".into() }),
+        wire().variant(8).str("abc"),
+    );
+}
+
+#[test]
+fn client_new_game() {
+    assert_client(
+        client::Message::NewGame(client::message::NewGame {
+            nick: "ann".into(),
+            grid: "abcdefghijklmnop".parse::<Grid>().unwrap(),
+            words: PackedDict::from_bytes(vec![0x80, 0x00]),
+            deadline: Utc.timestamp(0, 0),
+            dictionary: "english".into(),
+        }),
+        wire()
+            .variant(0)
+            .str("ann")
+            .chars("abcdefghijklmnop")
+            .bytes(&[0x80, 0x00])
+            .str("1970-01-01T00:00:00Z")
+            .str("english"),
+    );
+}
+
+/// Grids without any word still have to reach both kinds of clients.
+#[test]
+fn client_new_game_without_words() {
+    let new_game = || client::Message::NewGame(client::message::NewGame {
+        nick: "ann".into(),
+        grid: "xxxxxxxxxxxxxxxx".parse::<Grid>().unwrap(),
+        words: PackedDict::from(&Dict::new()),
+        deadline: Utc.timestamp(0, 0),
+        dictionary: "english".into(),
+    });
+
+    assert_client(
+        new_game(),
+        wire()
+            .variant(0)
+            .str("ann")
+            .chars("xxxxxxxxxxxxxxxx")
+            .bytes(&[0x00])
+            .str("1970-01-01T00:00:00Z")
+            .str("english"),
+    );
+
+    assert_client_json(
+        new_game(),
+        concat!(
+            r#"{"NewGame":{"nick":"ann","#,
+            r#""grid":{"chars":["x","x","x","x","x","x","x","x","x","x","x","x","x","x","x","x"]},"#,
+            r#""words":[],"#,
+            r#""deadline":"1970-01-01T00:00:00Z","dictionary":"english"}}"#,
+        ),
+    );
+
+    assert_eq!(PackedDict::default().unpack().unwrap(), Dict::new());
+    assert_eq!(PackedDict::from(&Dict::new()).unpack().unwrap(), Dict::new());
+}
+
+#[test]
+fn client_nick_already_in_use() {
+    assert_client(
+        client::Message::NickAlreadyInUse(client::message::NickAlreadyInUse { nick: "ann".into() }),
+        wire().variant(1).str("ann"),
+    );
+}
+
+#[test]
+fn client_player_status() {
+    use boggle_common::client::message::PlayerStatus;
+
+    assert_client(
+        client::Message::PlayerStatus(PlayerStatus::FoundWords { nick: "ann".into(), count: 3 }),
+        wire().variant(2).variant(0).str("ann").u64(3),
+    );
+
+    assert_client(
+        client::Message::PlayerStatus(PlayerStatus::Disconnected { nick: "ann".into() }),
+        wire().variant(2).variant(1).str("ann"),
+    );
+}
+
+#[test]
+fn client_dictionaries() {
+    assert_client(
+        client::Message::Dictionaries(client::message::Dictionaries {
+            dictionaries: vec![client::message::DictionaryInfo {
+                id: "english".into(),
+                description: "English".into(),
+            }],
+            selected: "english".into(),
+        }),
+        wire().variant(3).u64(1).str("english").str("English").str("english"),
+    );
+}
+
+#[test]
+fn client_definition() {
+    assert_client(
+        client::Message::Definition(client::message::Definition {
+            word: "tea".into(),
+            definition: Some("a beverage".into()),
+        }),
+        wire().variant(4).str("tea").u8(1).str("a beverage"),
+    );
+
+    assert_client(
+        client::Message::Definition(client::message::Definition {
+            word: "xyz".into(),
+            definition: None,
+        }),
+        wire().variant(4).str("xyz").u8(0),
+    );
+}
+
+#[test]
+fn client_welcome() {
+    assert_client(
+        client::Message::Welcome(client::message::Welcome {
+            protocol_version: 1,
+            capabilities: vec!["dictionaries".into()],
+        }),
+        wire().variant(5).u32(1).u64(1).str("dictionaries"),
+    );
+}
+
+#[test]
+fn client_incompatible() {
+    assert_client(
+        client::Message::Incompatible(client::message::Incompatible {
+            protocol_version: 2,
+            min_protocol_version: 2,
+            message: "too old".into(),
+        }),
+        wire().variant(6).u32(2).u32(2).str("too old"),
+    );
+}
+
+#[test]
+fn client_error() {
+    use boggle_common::client::message::ErrorCode;
+
+    assert_client(
+        client::Message::Error { code: ErrorCode::NickTaken, message: "taken".into() },
+        wire().variant(7).variant(4).str("taken"),
+    );
+
+    assert_client(
+        client::Message::Error { code: ErrorCode::RoomFull, message: "full".into() },
+        wire().variant(7).variant(7).str("full"),
+    );
+
+    assert_client(
+        client::Message::Error { code: ErrorCode::UnknownSession, message: "gone".into() },
+        wire().variant(7).variant(10).str("gone"),
+    );
+
+    assert_client(
+        client::Message::Error { code: ErrorCode::Banned, message: "bye".into() },
+        wire().variant(7).variant(11).str("bye"),
+    );
+
+    assert_client(
+        client::Message::Error { code: ErrorCode::NotAllowed, message: "no".into() },
+        wire().variant(7).variant(12).str("no"),
+    );
+}
+
+#[test]
+fn client_chat() {
+    assert_client(
+        client::Message::Chat(client::message::Chat { nick: "ann".into(), text: "hi".into() }),
+        wire().variant(8).str("ann").str("hi"),
+    );
+}
+
+#[test]
+fn client_round_results() {
+    assert_client(
+        client::Message::RoundResults(client::message::RoundResults {
+            grid: "abcdefghijklmnop".parse::<Grid>().unwrap(),
+            players: vec![client::message::PlayerResult {
+                nick: "ann".into(),
+                words: vec!["fab".into()],
+                score: 1,
+            }],
+        }),
+        wire()
+            .variant(9)
+            .chars("abcdefghijklmnop")
+            .u64(1)
+            .str("ann")
+            .u64(1)
+            .str("fab")
+            .u32(1),
+    );
+}
+
+#[test]
+fn client_shutdown() {
+    assert_client(
+        client::Message::Shutdown(client::message::Shutdown { message: "bye".into() }),
+        wire().variant(10).str("bye"),
+    );
+}
+
+#[test]
+fn client_session() {
+    assert_client(
+        client::Message::Session(client::message::Session {
+            token: "abc".into(),
+            words: vec!["fab".into()],
+        }),
+        wire().variant(11).str("abc").u64(1).str("fab"),
+    );
+}
+
+#[test]
+fn json_server() {
+    assert_server_json(
+        server::Message::Hello(server::message::Hello {
+            protocol_version: 1,
+            capabilities: Vec::new(),
+        }),
+        r#"{"Hello":{"protocol_version":1,"capabilities":[]}}"#,
+    );
+
+    assert_server_json(
+        server::Message::Login(server::message::Login { nick: "ann".into() }),
+        r#"{"Login":{"nick":"ann"}}"#,
+    );
+
+    assert_server_json(
+        server::Message::ListDictionaries,
+        r#""ListDictionaries""#,
+    );
+}
+
+#[test]
+fn json_client() {
+    use boggle_common::client::message::PlayerStatus;
+
+    assert_client_json(
+        client::Message::NewGame(client::message::NewGame {
+            nick: "ann".into(),
+            grid: "teasornildcumphq".parse::<Grid>().unwrap(),
+            words: PackedDict::from(&["tea", "eat", "sea"].iter().cloned().collect::<Dict>()),
+            deadline: Utc.timestamp(0, 0),
+            dictionary: "english".into(),
+        }),
+        concat!(
+            r#"{"NewGame":{"nick":"ann","#,
+            r#""grid":{"chars":["t","e","a","s","o","r","n","i","l","d","c","u","m","p","h","q"]},"#,
+            r#""words":["eat","sea","tea"],"#,
+            r#""deadline":"1970-01-01T00:00:00Z","dictionary":"english"}}"#,
+        ),
+    );
+
+    assert_client_json(
+        client::Message::PlayerStatus(PlayerStatus::FoundWords { nick: "ann".into(), count: 3 }),
+        r#"{"PlayerStatus":{"FoundWords":{"nick":"ann","count":3}}}"#,
+    );
+
+    assert_client_json(
+        client::Message::Definition(client::message::Definition {
+            word: "xyz".into(),
+            definition: None,
+        }),
+        r#"{"Definition":{"word":"xyz","definition":null}}"#,
+    );
+}
@@ -3,18 +3,25 @@ extern crate boggle;
 use std::env;
 use std::fs::File;
 use boggle::Dict;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 fn main() {
     let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
 
-    let mut dict = Dict::new();
-    let words = include_str!("dict");
+    let dict: Dict = include_str!("dict").split_whitespace().collect();
+    // Restricted to the full list, so every builtin list agrees with it
+    let common = dict.intersection(&include_str!("common").split_whitespace().collect());
+    let kids = dict.intersection(&include_str!("kids").split_whitespace().collect());
+    // Tournament play only counts words of at least four letters
+    let tournament: Dict = dict.words().filter(|word| word.chars().count() >= 4).collect();
 
-    for word in words.split_whitespace() {
-        dict.add(word);
-    }
+    write(&out_dir, "dict", &dict);
+    write(&out_dir, "common", &common);
+    write(&out_dir, "kids", &kids);
+    write(&out_dir, "tournament", &tournament);
+}
 
-    let mut dict_file = File::create(out_dir.join("dict")).unwrap();
-    dict.serialize_packed(&mut dict_file).unwrap();
+fn write(out_dir: &Path, name: &str, dict: &Dict) {
+    let mut file = File::create(out_dir.join(name)).unwrap();
+    dict.serialize_packed(&mut file).unwrap();
 }
@@ -3,9 +3,23 @@ extern crate boggle;
 
 use boggle::Dict;
 
+mod registry;
+pub use self::registry::{Registry, Entry, DEFAULT_ID};
+
+static PACKED_DICT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/dict"));
+static PACKED_COMMON: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/common"));
+static PACKED_KIDS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/kids"));
+static PACKED_TOURNAMENT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/tournament"));
+
 lazy_static! {
     pub static ref DICT: Dict = {
-        let mut dict = include_bytes!(concat!(env!("OUT_DIR"), "/dict")).as_ref();
+        let mut dict = PACKED_DICT;
+        Dict::deserialize_packed(&mut dict).unwrap()
+    };
+
+    /// The words of `DICT` that are common in everyday English.
+    pub static ref COMMON: Dict = {
+        let mut dict = PACKED_COMMON;
         Dict::deserialize_packed(&mut dict).unwrap()
     };
 }
@@ -0,0 +1,138 @@
+use boggle::{Dict, Definitions};
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::path::Path;
+use std::sync::Arc;
+use {PACKED_DICT, PACKED_COMMON, PACKED_KIDS, PACKED_TOURNAMENT};
+
+/// Id of the dictionary that is compiled into this crate.
+pub const DEFAULT_ID: &str = "english";
+
+/// Dictionaries that are compiled into this crate as `(id, description, packed words)`.
+const BUILTIN: &[(&str, &str, &[u8])] = &[
+    (DEFAULT_ID, "Full English word list", PACKED_DICT),
+    ("common", "Common English words", PACKED_COMMON),
+    ("kids", "Simple English words for children", PACKED_KIDS),
+    ("tournament", "Full English word list, only words of at least four letters", PACKED_TOURNAMENT),
+];
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub id: String,
+    pub description: String,
+    pub dict: Arc<Dict>,
+    pub definitions: Option<Arc<Definitions>>,
+}
+
+/// A set of dictionaries, identified by their id.
+#[derive(Debug, Clone, Default)]
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Create a registry containing all compiled in dictionaries.
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+
+        for &(id, description, mut packed) in BUILTIN {
+            let dict = Dict::deserialize_packed(&mut packed).expect("invalid builtin dictionary");
+
+            registry.insert(Entry {
+                id: id.into(),
+                description: description.into(),
+                dict: Arc::new(dict),
+                definitions: None,
+            });
+        }
+
+        registry
+    }
+
+    /// Add a dictionary, replacing any dictionary with the same id.
+    pub fn insert(&mut self, entry: Entry) {
+        match self.entries.iter_mut().find(|e| e.id == entry.id) {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.get(id).is_some()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter()
+    }
+
+    /// Load every packed dictionary (`<id>.dict`) from `dir`.
+    ///
+    /// See [`load_file`](#method.load_file) for how descriptions are found.
+    pub fn load_dir<P: AsRef<Path>>(&mut self, dir: P) -> io::Result<()> {
+        let mut paths = Vec::new();
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            if path.extension().map_or(false, |ext| ext == Dict::PACKED_EXTENSION) {
+                paths.push(path);
+            }
+        }
+
+        // Keep the order independent of the file system
+        paths.sort();
+
+        for path in paths {
+            self.load_file(path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Load a packed dictionary or plain text word list from `path`.
+    /// Files ending in `.dict` are packed, see `Dict::open`.
+    ///
+    /// The id of the dictionary is the file name without extension.
+    /// Its description is read from `<id>.desc` and its definitions
+    /// from `<id>.defs` next to it if those exist.
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let id = path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid dictionary name: {}", path.display())))?
+            .to_owned();
+
+        let description = match fs::read_to_string(path.with_extension("desc")) {
+            Ok(description) => description.trim().to_owned(),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => id.clone(),
+            Err(e) => return Err(e),
+        };
+
+        let dict = Dict::open(path)?;
+
+        let definitions = match File::open(path.with_extension("defs")) {
+            Ok(file) => Some(Arc::new(Definitions::deserialize(BufReader::new(file))?)),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+
+        self.insert(Entry {
+            id,
+            description,
+            dict: Arc::new(dict),
+            definitions,
+        });
+
+        Ok(())
+    }
+}
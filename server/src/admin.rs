@@ -0,0 +1,125 @@
+//! Commands for operators, see `common/API.md`.
+
+use ::actix::prelude::*;
+use actix_web::{web, HttpRequest, HttpResponse};
+use actix_web::error::BlockingError;
+use actix_web::http::header::AUTHORIZATION;
+use dict::Registry;
+use failure::{Error, bail};
+use std::io;
+use std::sync::Arc;
+use tracing::{info, error};
+use crate::{Server, ReloadDictionaries};
+
+/// What the commands need, expected as app data.
+pub struct Admin {
+    /// Token that authenticates operators, commands are disabled if `None`
+    pub token: Option<String>,
+    /// Loads the dictionaries the server was configured with
+    pub load_dictionaries: Arc<dyn Fn() -> io::Result<Registry> + Send + Sync>,
+}
+
+impl Admin {
+    /// Load all dictionaries again and hand them to `server`.
+    /// They are used starting with the next game.
+    pub async fn reload(&self, server: &Addr<Server>) -> Result<(), Error> {
+        let load = self.load_dictionaries.clone();
+
+        let dicts = match web::block(move || load()).await {
+            Ok(dicts) => dicts,
+            Err(BlockingError::Error(e)) => return Err(e.into()),
+            Err(BlockingError::Canceled) => bail!("Loading dictionaries was canceled"),
+        };
+
+        server.send(ReloadDictionaries { dicts }).await?
+    }
+
+    /// Whether `req` carries the token as `Authorization: Bearer <token>`.
+    fn authorized(&self, req: &HttpRequest) -> bool {
+        let token = match &self.token {
+            Some(token) => token,
+            None => return false,
+        };
+
+        let given = req.headers().get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .unwrap_or("");
+
+        // Compared without stopping at the first difference, so the token can't be guessed byte by byte
+        given.len() == token.len() && given.bytes().zip(token.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+    }
+}
+
+/// Mount the commands under `/admin`.
+/// Expects the server's address and the `Admin` as app data.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/admin/reload", web::post().to(reload));
+}
+
+async fn reload(req: HttpRequest, server: web::Data<Addr<Server>>, admin: web::Data<Admin>) -> HttpResponse {
+    if admin.token.is_none() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    if !admin.authorized(&req) {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    match admin.reload(&server).await {
+        Ok(()) => {
+            info!("Reloaded dictionaries");
+            HttpResponse::NoContent().finish()
+        },
+        Err(e) => {
+            error!(error = %e, "Failed to reload dictionaries");
+            HttpResponse::InternalServerError().body(e.to_string())
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+    use super::*;
+
+    const TOKEN: &str = "0123456789abcdef";
+
+    fn admin(token: Option<&str>) -> Admin {
+        Admin {
+            token: token.map(String::from),
+            load_dictionaries: Arc::new(|| Ok(Registry::builtin())),
+        }
+    }
+
+    fn request(authorization: Option<&str>) -> HttpRequest {
+        let mut req = TestRequest::post();
+
+        if let Some(authorization) = authorization {
+            req = req.header(AUTHORIZATION, authorization);
+        }
+
+        req.to_http_request()
+    }
+
+    #[test]
+    fn bearer_token() {
+        assert!(admin(Some(TOKEN)).authorized(&request(Some("Bearer 0123456789abcdef"))));
+    }
+
+    #[test]
+    fn wrong_token() {
+        let admin = admin(Some(TOKEN));
+
+        assert!(!admin.authorized(&request(None)));
+        assert!(!admin.authorized(&request(Some("0123456789abcdef"))));
+        assert!(!admin.authorized(&request(Some("Bearer 0123456789abcdeF"))));
+        assert!(!admin.authorized(&request(Some("Bearer 0123456789abcdef0"))));
+        assert!(!admin.authorized(&request(Some("Bearer "))));
+    }
+
+    #[test]
+    fn disabled_without_token() {
+        assert!(!admin(None).authorized(&request(Some("Bearer "))));
+    }
+}
@@ -0,0 +1,190 @@
+use boggle::Dict;
+use dict::COMMON;
+use rand::Rng;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use failure::Error;
+
+/// How well a bot plays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Skill {
+    /// Share of the words the bot knows, from 0 to 1.
+    /// Common words are known first.
+    pub vocabulary: f64,
+    /// How many of its known words the bot finds per minute on average
+    pub words_per_minute: f64,
+    /// Relative preference for words by length, starting at 3 letters.
+    /// Longer words use the last weight.
+    pub length_weights: Vec<f64>,
+}
+
+impl Skill {
+    pub fn easy() -> Self {
+        Self {
+            vocabulary: 0.3,
+            words_per_minute: 3.0,
+            length_weights: vec![4.0, 2.0, 0.5, 0.1],
+        }
+    }
+
+    pub fn medium() -> Self {
+        Self {
+            vocabulary: 0.6,
+            words_per_minute: 6.0,
+            length_weights: vec![3.0, 3.0, 1.0, 0.5],
+        }
+    }
+
+    pub fn hard() -> Self {
+        Self {
+            vocabulary: 0.9,
+            words_per_minute: 12.0,
+            length_weights: vec![1.0, 2.0, 3.0, 3.0],
+        }
+    }
+
+    fn length_weight(&self, word: &str) -> f64 {
+        let index = word.chars().count().saturating_sub(3);
+
+        self.length_weights.get(index)
+            .or_else(|| self.length_weights.last())
+            .cloned()
+            .unwrap_or(1.0)
+    }
+}
+
+impl FromStr for Skill {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s {
+            "easy" => Ok(Skill::easy()),
+            "medium" => Ok(Skill::medium()),
+            "hard" => Ok(Skill::hard()),
+            _ => bail!("Unknown bot skill '{}', expected easy, medium or hard", s),
+        }
+    }
+}
+
+/// A computer player that finds words over the course of a game.
+#[derive(Debug, Clone)]
+pub struct Bot {
+    nick: String,
+    skill: Skill,
+    /// Words that will be found this game, next one last
+    plan: Vec<String>,
+}
+
+impl Bot {
+    pub fn new(nick: String, skill: Skill) -> Self {
+        Self {
+            nick,
+            skill,
+            plan: Vec::new(),
+        }
+    }
+
+    pub fn nick(&self) -> &str {
+        &self.nick
+    }
+
+    /// Decide which words of a new game will be found, and in which order.
+    pub fn new_game<R: Rng>(&mut self, words: &Dict, rng: &mut R) {
+        // Weighted random order (Efraimidis–Spirakis): a larger weight means an earlier find
+        let mut plan: Vec<(f64, &str)> = self.known_words(words).into_iter()
+            .map(|word| (rng.gen::<f64>().powf(1.0 / self.skill.length_weight(word)), word))
+            .collect();
+
+        plan.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        self.plan = plan.into_iter().map(|(_, word)| word.to_owned()).collect();
+    }
+
+    /// Drop words from the plan that were found already, e.g. before a restart.
+    pub fn skip_found(&mut self, found: &HashSet<String>) {
+        self.plan.retain(|word| !found.contains(word));
+    }
+
+    /// Advance the bot by one second, returning the word it found if any.
+    pub fn tick<R: Rng>(&mut self, rng: &mut R) -> Option<String> {
+        let chance = (self.skill.words_per_minute / 60.0).min(1.0).max(0.0);
+
+        if !rng.gen_bool(chance) {
+            return None;
+        }
+
+        self.plan.pop()
+    }
+
+    /// The share of `words` that is part of the bot's vocabulary.
+    ///
+    /// Words of the builtin `common` list are known first. Which words
+    /// within either group a bot knows is fixed per bot and word.
+    fn known_words<'a>(&self, words: &'a Dict) -> Vec<&'a str> {
+        let mut ranked: Vec<(bool, u64, &str)> = words.words()
+            .map(|word| {
+                let mut hasher = DefaultHasher::new();
+                (&self.nick, word).hash(&mut hasher);
+
+                (!COMMON.contains(word), hasher.finish(), word)
+            })
+            .collect();
+
+        ranked.sort();
+
+        let known = (ranked.len() as f64 * self.skill.vocabulary.min(1.0).max(0.0)).round() as usize;
+
+        ranked.into_iter().take(known).map(|(_, _, word)| word).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use super::*;
+
+    const COMMON_WORDS: &[&str] = &["cat", "dog", "house", "water"];
+    const RARE_WORDS: &[&str] = &["abet", "acyl", "adze", "aery", "agar", "agog", "ague"];
+
+    fn bot(vocabulary: f64) -> Bot {
+        Bot::new("bot".into(), Skill { vocabulary, ..Skill::easy() })
+    }
+
+    fn words() -> Dict {
+        COMMON_WORDS.iter().chain(RARE_WORDS).cloned().collect()
+    }
+
+    #[test]
+    fn small_vocabulary_knows_common_words() {
+        let words = words();
+        let known = bot(0.3).known_words(&words);
+
+        assert_eq!(known.len(), 3);
+        assert!(known.iter().all(|word| COMMON_WORDS.contains(word)), "{:?}", known);
+    }
+
+    #[test]
+    fn large_vocabulary_knows_rare_words() {
+        let words = words();
+        let known = bot(0.9).known_words(&words);
+
+        assert_eq!(known.len(), 10);
+        assert!(COMMON_WORDS.iter().all(|word| known.contains(word)), "{:?}", known);
+    }
+
+    #[test]
+    fn found_words_are_skipped() {
+        let mut bot = bot(1.0);
+        bot.new_game(&words(), &mut StdRng::seed_from_u64(0));
+        bot.skip_found(&["dog", "adze"].iter().map(|&word| word.to_owned()).collect());
+
+        let mut plan = bot.plan.clone();
+        plan.sort();
+
+        assert_eq!(plan, vec!["abet", "acyl", "aery", "agar", "agog", "ague", "cat", "house", "water"]);
+    }
+}
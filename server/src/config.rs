@@ -0,0 +1,298 @@
+use boggle::Dice;
+use boggle_server::{Settings, Skill, Limits, Rate};
+use chrono::Duration;
+use failure::{Error, ResultExt, bail, ensure, format_err};
+use serde::Deserialize;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use tracing_subscriber::EnvFilter;
+
+/// Contents of the configuration file, see `config.example.toml`.
+///
+/// Every value has a default, so the file and each of its sections are optional.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub game: GameConfig,
+    pub dictionaries: DictionariesConfig,
+    pub limits: LimitsConfig,
+    pub persistence: PersistenceConfig,
+    pub log: LogConfig,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+    /// Directory with the web UI built by `cargo web deploy`, not served if `None`
+    pub static_dir: Option<PathBuf>,
+    /// Proxies whose `X-Forwarded-For` headers name the client's address
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Token for the commands under `/admin`, which are disabled if `None`
+    pub admin_token: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    /// In seconds
+    pub round: u64,
+    /// In seconds
+    pub intermission: u64,
+    /// `standard`, `classic`, `revised` or sixteen custom dice
+    pub dice: String,
+    pub dictionary: String,
+    pub bots: usize,
+    pub bot_skill: String,
+    pub chat_during_play: bool,
+    pub reveal_results: bool,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct DictionariesConfig {
+    pub dir: Option<PathBuf>,
+    pub files: Vec<PathBuf>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitsConfig {
+    pub max_players: Option<usize>,
+    /// Ended rounds kept for the HTTP API
+    pub history: usize,
+    /// In bytes
+    pub max_frame_size: usize,
+    pub max_invalid_words: usize,
+    pub submissions: Rate,
+    pub logins: Rate,
+    pub chat: Rate,
+    pub max_violations: u32,
+    /// In seconds
+    pub ban: u64,
+    /// Deprecated, replaced by `chat`
+    pub chat_messages: Option<usize>,
+    /// Deprecated, replaced by `chat`, in seconds
+    pub chat_window: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct PersistenceConfig {
+    pub replay_dir: Option<PathBuf>,
+    pub snapshot: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    /// `off`, `error`, `warn`, `info`, `debug`, `trace` or a filter like `info,boggle_server=debug`
+    pub level: String,
+    /// `text` or `json`, one object per line
+    pub format: String,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let config = fs::read_to_string(path)
+            .with_context(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let config = toml::from_str(&config)
+            .with_context(|e| format!("Invalid config file {}: {}", path.display(), e))?;
+
+        Ok(config)
+    }
+
+    /// Check the values that can be checked without loading anything.
+    pub fn validate(&self) -> Result<(), Error> {
+        let game = &self.game;
+        let limits = &self.limits;
+
+        if let Some(dir) = &self.server.static_dir {
+            ensure!(dir.join("index.html").is_file(), "server.static_dir {} contains no index.html", dir.display());
+        }
+
+        if let Some(token) = &self.server.admin_token {
+            ensure!(token.len() >= 16, "server.admin_token must be at least 16 characters");
+        }
+
+        ensure!(game.round > 0, "game.round must be at least one second");
+        ensure!(!game.dictionary.is_empty(), "game.dictionary must not be empty");
+        ensure!(limits.max_players != Some(0), "limits.max_players must be at least 1, omit it for no limit");
+        ensure!(limits.max_frame_size >= 64, "limits.max_frame_size must be at least 64 bytes");
+
+        ensure!(limits.chat_messages != Some(0), "limits.chat_messages must be at least 1");
+        ensure!(limits.chat_window != Some(0), "limits.chat_window must be at least one second");
+
+        for (name, rate) in &[("submissions", limits.submissions), ("logins", limits.logins), ("chat", self.chat_rate())] {
+            ensure!(rate.burst > 0, "limits.{}.burst must be at least 1", name);
+            ensure!(rate.per_second > 0.0, "limits.{}.per_second must be positive", name);
+        }
+
+        self.dice()?;
+        self.bot_skill()?;
+        self.log_filter()?;
+        self.log_json()?;
+
+        Ok(())
+    }
+
+    /// Warnings about deprecated settings that are still in use.
+    pub fn deprecations(&self) -> Vec<String> {
+        let mut deprecations = Vec::new();
+
+        if self.limits.chat_messages.is_some() || self.limits.chat_window.is_some() {
+            let chat = self.chat_rate();
+
+            deprecations.push(format!(
+                "limits.chat_messages and limits.chat_window are deprecated, \
+                 replace them with burst = {} and per_second = {} in [limits.chat]",
+                chat.burst, chat.per_second,
+            ));
+        }
+
+        deprecations
+    }
+
+    /// `limits.chat`, unless the deprecated `chat_messages` or `chat_window` are set.
+    fn chat_rate(&self) -> Rate {
+        let limits = &self.limits;
+
+        if limits.chat_messages.is_none() && limits.chat_window.is_none() {
+            return limits.chat;
+        }
+
+        // The defaults these had before being replaced
+        let messages = limits.chat_messages.unwrap_or(5);
+        let window = limits.chat_window.unwrap_or(10);
+
+        Rate {
+            burst: messages as u32,
+            per_second: messages as f64 / window as f64,
+        }
+    }
+
+    /// Dice to roll grids with, `None` for weighted random letters.
+    pub fn dice(&self) -> Result<Option<Dice>, Error> {
+        let dice = match &*self.game.dice {
+            "standard" => None,
+            "classic" => Some(Dice::classic()),
+            "revised" => Some(Dice::revised()),
+            dice => Some(dice.parse::<Dice>().with_context(|e| format!("game.dice: {}", e))?),
+        };
+
+        Ok(dice)
+    }
+
+    pub fn bot_skill(&self) -> Result<Skill, Error> {
+        let skill = self.game.bot_skill.parse::<Skill>()
+            .with_context(|e| format!("game.bot_skill: {}", e))?;
+
+        Ok(skill)
+    }
+
+    pub fn log_filter(&self) -> Result<EnvFilter, Error> {
+        let filter = EnvFilter::try_new(&self.log.level)
+            .map_err(|e| format_err!("log.level '{}' is invalid: {}", self.log.level, e))?;
+
+        Ok(filter)
+    }
+
+    /// Whether to log JSON instead of text.
+    pub fn log_json(&self) -> Result<bool, Error> {
+        match &*self.log.format {
+            "text" => Ok(false),
+            "json" => Ok(true),
+            format => bail!("log.format '{}' is invalid, expected text or json", format),
+        }
+    }
+
+    pub fn settings(&self) -> Result<Settings, Error> {
+        Ok(Settings {
+            dictionary: self.game.dictionary.clone(),
+            round: Duration::seconds(self.game.round as i64),
+            intermission: Duration::seconds(self.game.intermission as i64),
+            dice: self.dice()?,
+            max_players: self.limits.max_players,
+            chat_during_play: self.game.chat_during_play,
+            max_invalid_words: self.limits.max_invalid_words,
+            reveal_results: self.game.reveal_results,
+            replay_dir: self.persistence.replay_dir.clone(),
+            snapshot: self.persistence.snapshot.clone(),
+            history: self.limits.history,
+        })
+    }
+
+    /// Limits of every connection.
+    pub fn limits(&self) -> Limits {
+        Limits {
+            submissions: self.limits.submissions,
+            logins: self.limits.logins,
+            chat: self.chat_rate(),
+            max_violations: self.limits.max_violations,
+            ban: Duration::seconds(self.limits.ban as i64),
+            max_frame_size: self.limits.max_frame_size,
+        }
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".into(),
+            port: 8001,
+            static_dir: None,
+            trusted_proxies: Vec::new(),
+            admin_token: None,
+        }
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        let settings = Settings::default();
+
+        Self {
+            round: settings.round.num_seconds() as u64,
+            intermission: settings.intermission.num_seconds() as u64,
+            dice: "standard".into(),
+            dictionary: settings.dictionary,
+            bots: 0,
+            bot_skill: "medium".into(),
+            chat_during_play: settings.chat_during_play,
+            reveal_results: settings.reveal_results,
+        }
+    }
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        let settings = Settings::default();
+        let limits = Limits::default();
+
+        Self {
+            max_players: settings.max_players,
+            history: settings.history,
+            max_frame_size: limits.max_frame_size,
+            max_invalid_words: settings.max_invalid_words,
+            submissions: limits.submissions,
+            logins: limits.logins,
+            chat: limits.chat,
+            max_violations: limits.max_violations,
+            ban: limits.ban.num_seconds() as u64,
+            chat_messages: None,
+            chat_window: None,
+        }
+    }
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".into(),
+            format: "text".into(),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use failure::Fail;
+use actix_web_actors::ws;
+use boggle_common::client;
+use boggle_common::client::message::ErrorCode;
+
+/// An error that is reported to the client that caused it.
+#[derive(Fail, Debug, Clone)]
+#[fail(display = "{:?}: {}", code, message)]
+pub struct ClientError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl ClientError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn internal() -> Self {
+        Self::new(ErrorCode::Internal, "Internal server error")
+    }
+
+    /// Reason of the close frame that ends the connection after a fatal error.
+    pub fn close_reason(&self) -> ws::CloseReason {
+        let code = match self.code {
+            ErrorCode::Internal => ws::CloseCode::Error,
+            ErrorCode::MalformedMessage => ws::CloseCode::Invalid,
+            ErrorCode::RateLimited | ErrorCode::Banned => ws::CloseCode::Policy,
+            ErrorCode::RoomFull => ws::CloseCode::Again,
+            _ => ws::CloseCode::Protocol,
+        };
+
+        ws::CloseReason {
+            code,
+            description: Some(self.message.clone()),
+        }
+    }
+}
+
+impl From<ClientError> for client::Message {
+    fn from(error: ClientError) -> Self {
+        client::Message::Error {
+            code: error.code,
+            message: error.message,
+        }
+    }
+}
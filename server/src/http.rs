@@ -0,0 +1,79 @@
+//! Read-only JSON API for integrations that don't speak the websocket protocol,
+//! and metrics for monitoring.
+//!
+//! See `common/API.md` for the endpoints.
+
+use ::actix::prelude::*;
+use actix_web::{web, HttpResponse};
+use actix_web::error::ErrorServiceUnavailable;
+use boggle_common::{api, PROTOCOL_VERSION};
+use serde::Deserialize;
+use crate::{Server, GetRooms, GetScores, GetHistory, GetLeaderboard, GetUptime};
+use crate::metrics::METRICS;
+
+/// Rounds returned by `/api/history` unless `limit` is given
+const DEFAULT_HISTORY_LIMIT: usize = 10;
+
+/// Mount the API under `/api` and the metrics at `/metrics`.
+/// Expects the server's address as app data.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/metrics", web::get().to(metrics));
+    cfg.service(
+        web::scope("/api")
+            .route("/health", web::get().to(health))
+            .route("/rooms", web::get().to(rooms))
+            .route("/scores", web::get().to(scores))
+            .route("/history", web::get().to(history))
+            .route("/leaderboard", web::get().to(leaderboard))
+    );
+}
+
+async fn health(server: web::Data<Addr<Server>>) -> HttpResponse {
+    match server.send(GetUptime).await {
+        Ok(uptime) => HttpResponse::Ok().json(api::Health {
+            status: "ok".into(),
+            protocol_version: PROTOCOL_VERSION,
+            uptime: uptime.num_seconds().max(0) as u64,
+        }),
+        Err(_) => HttpResponse::ServiceUnavailable().json(api::Health {
+            status: "unavailable".into(),
+            protocol_version: PROTOCOL_VERSION,
+            uptime: 0,
+        }),
+    }
+}
+
+async fn rooms(server: web::Data<Addr<Server>>) -> actix_web::Result<HttpResponse> {
+    let rooms = server.send(GetRooms).await.map_err(ErrorServiceUnavailable)?;
+    Ok(HttpResponse::Ok().json(rooms))
+}
+
+async fn scores(server: web::Data<Addr<Server>>) -> actix_web::Result<HttpResponse> {
+    let scores = server.send(GetScores).await.map_err(ErrorServiceUnavailable)?;
+    Ok(HttpResponse::Ok().json(scores))
+}
+
+#[derive(Deserialize)]
+struct HistoryQuery {
+    limit: Option<usize>,
+}
+
+async fn history(
+    server: web::Data<Addr<Server>>,
+    query: web::Query<HistoryQuery>,
+) -> actix_web::Result<HttpResponse> {
+    let limit = query.limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+    let history = server.send(GetHistory { limit }).await.map_err(ErrorServiceUnavailable)?;
+    Ok(HttpResponse::Ok().json(history))
+}
+
+async fn leaderboard(server: web::Data<Addr<Server>>) -> actix_web::Result<HttpResponse> {
+    let leaderboard = server.send(GetLeaderboard).await.map_err(ErrorServiceUnavailable)?;
+    Ok(HttpResponse::Ok().json(leaderboard))
+}
+
+async fn metrics() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(METRICS.render())
+}
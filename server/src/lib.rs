@@ -1,46 +1,381 @@
 #[macro_use] extern crate failure;
-#[macro_use] extern crate lazy_static;
+#[macro_use] extern crate tracing;
 
 use ::actix::prelude::*;
 use actix_web_actors::ws;
-use boggle::{Grid, Dict};
+use boggle::{Grid, Dict, PackedDict, points};
+use rand::distributions::{Alphanumeric, Standard};
 use rand::{Rng, thread_rng};
-use dict::DICT;
-use boggle_common::{client, server};
-use std::collections::{HashMap, HashSet};
+use dict::Registry;
+use boggle_common::{client, server, capability, Codec, PROTOCOL_VERSION, MIN_PROTOCOL_VERSION, MAX_CHAT_LENGTH};
+use boggle_common::client::message::ErrorCode;
+use boggle_common::replay::{Event, Verdict};
+use boggle_common::api;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::Path;
 use failure::Error;
-use chrono::{DateTime, Utc, Duration};
+use chrono::{DateTime, Utc};
 use ws::ProtocolError;
 
-lazy_static! {
-    static ref INTERVAL: Duration = Duration::minutes(10);
-}
+mod bot;
+pub use self::bot::{Bot, Skill};
+
+mod error;
+pub use self::error::ClientError;
+
+mod replay;
+use self::replay::ReplayLog;
+
+mod snapshot;
+use self::snapshot::{Snapshot, PlayerSnapshot};
+
+mod limit;
+pub use self::limit::{Limits, Rate};
+use self::limit::Bucket;
+
+mod settings;
+pub use self::settings::Settings;
+
+pub mod http;
+
+pub mod websocket;
+
+pub mod admin;
+
+pub mod metrics;
+use self::metrics::METRICS;
 
 pub struct Server {
     players: HashMap<Addr<Client>, Player>,
+    /// Players of a restored game that didn't resume yet, by session token
+    detached: HashMap<String, Player>,
+    /// Clients that watch the game without playing
+    spectators: HashSet<Addr<Client>>,
+    bots: Vec<(Bot, Player)>,
     grid: Grid,
     words: Dict,
+    /// `words` in the form that is sent to clients
+    packed_words: PackedDict,
+    started: DateTime<Utc>,
     deadline: DateTime<Utc>,
+    dicts: Registry,
+    /// Dictionaries that replace `dicts` when the next game starts
+    reloaded_dicts: Option<Registry>,
+    settings: Settings,
+    /// Dictionary of the current game
+    dictionary: String,
+    /// Dictionary that will be used for the next game
+    next_dictionary: String,
+    /// Definitions of the words of the last ended round, the only ones `Define` answers
+    definitions: HashMap<String, String>,
+    /// Replay log of the current round
+    replay: Option<ReplayLog>,
+    /// Ended rounds, oldest first
+    history: VecDeque<api::Round>,
+    /// When the server was created
+    launched: DateTime<Utc>,
+    /// Until when addresses of abusive clients can't connect
+    bans: HashMap<String, DateTime<Utc>>,
 }
 
 impl Server {
-    pub fn new() -> Self {
-        Self {
+    pub fn new(dicts: Registry, settings: Settings) -> Result<Self, Error> {
+        ensure!(dicts.contains(&settings.dictionary), "Unknown dictionary '{}'", settings.dictionary);
+
+        Ok(Self {
             players: HashMap::new(),
+            detached: HashMap::new(),
+            spectators: HashSet::new(),
+            bots: Vec::new(),
             grid: Grid::default(),
             words: Dict::new(),
+            packed_words: PackedDict::default(),
+            started: Utc::now(),
             deadline: Utc::now(),
+            dicts,
+            reloaded_dicts: None,
+            dictionary: settings.dictionary.clone(),
+            next_dictionary: settings.dictionary.clone(),
+            definitions: HashMap::new(),
+            settings,
+            replay: None,
+            history: VecDeque::new(),
+            launched: Utc::now(),
+            bans: HashMap::new(),
+        })
+    }
+
+    /// Add a computer player, which joins every game.
+    pub fn add_bot(&mut self, bot: Bot) -> Result<(), Error> {
+        ensure!(!self.nick_in_use(bot.nick()), "Nick '{}' is already in use", bot.nick());
+
+        let player = Player::new(bot.nick().to_owned());
+        self.bots.push((bot, player));
+
+        Ok(())
+    }
+
+    fn log(&mut self, event: Event) {
+        if let Some(replay) = &mut self.replay {
+            if let Err(e) = replay.log(&event) {
+                error!(path = %replay.path().display(), error = %e, "Failed to write replay log");
+            }
+        }
+    }
+
+    /// Start the replay log of the current round.
+    fn start_replay(&mut self) {
+        let dir = match &self.settings.replay_dir {
+            Some(dir) => dir,
+            None => return,
+        };
+
+        let now = self.started;
+
+        match ReplayLog::create(dir, now) {
+            Ok(replay) => self.replay = Some(replay),
+            Err(e) => {
+                error!(dir = %dir.display(), error = %e, "Failed to create replay log");
+                return;
+            },
+        }
+
+        let mut words: Vec<String> = self.words.words().map(String::from).collect();
+        words.sort();
+
+        self.log(Event::RoundStarted {
+            time: now,
+            grid: self.grid,
+            dictionary: self.dictionary.clone(),
+            deadline: self.deadline,
+            words,
+        });
+
+        let joined: Vec<(String, bool)> = self.players.values()
+            .map(|player| (player.nick.clone(), false))
+            .chain(self.bots.iter().map(|(_, player)| (player.nick.clone(), true)))
+            .collect();
+
+        for (nick, bot) in joined {
+            self.log(Event::Joined {
+                time: now,
+                nick,
+                bot,
+            });
+        }
+    }
+
+    /// Everybody who receives game updates, players and spectators.
+    fn clients(&self) -> impl Iterator<Item = &Addr<Client>> {
+        self.players.keys().chain(&self.spectators)
+    }
+
+    fn new_game(&self, nick: String) -> client::Message {
+        client::Message::NewGame(client::message::NewGame {
+            nick,
+            grid: self.grid.clone(),
+            words: self.packed_words.clone(),
+            deadline: self.deadline.clone(),
+            dictionary: self.dictionary.clone(),
+        })
+    }
+
+    /// Everybody in the game, including bots and players that didn't resume yet.
+    fn all_players(&self) -> impl Iterator<Item = &Player> {
+        self.players.values()
+            .chain(self.detached.values())
+            .chain(self.bots.iter().map(|(_, player)| player))
+    }
+
+    /// Current word counts of all players, for somebody who just joined.
+    fn send_player_statuses(&self, client: &Addr<Client>) {
+        use self::client::message::PlayerStatus;
+
+        for player in self.all_players() {
+            client.do_send(client::Message::PlayerStatus(PlayerStatus::FoundWords {
+                nick: player.nick.clone(),
+                count: player.found_words.len(),
+            }).into());
+        }
+    }
+
+    /// Scores of the current round, without the words.
+    fn scores(&self) -> Vec<api::Score> {
+        let players = self.players.values().chain(self.detached.values()).map(|player| (player, false));
+        let bots = self.bots.iter().map(|(_, player)| (player, true));
+        let mut scores: Vec<api::Score> = players.chain(bots)
+            .map(|(player, bot)| api::Score {
+                nick: player.nick.clone(),
+                bot,
+                words: player.found_words.len(),
+                score: player.found_words.iter().map(|word| points(word)).sum(),
+            })
+            .collect();
+
+        scores.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.nick.cmp(&b.nick)));
+        scores
+    }
+
+    fn round_results(&self) -> client::message::RoundResults {
+        use self::client::message::{RoundResults, PlayerResult};
+
+        let mut players: Vec<PlayerResult> = self.all_players()
+            .map(|player| {
+                let mut words: Vec<String> = player.found_words.iter().cloned().collect();
+                words.sort();
+
+                PlayerResult {
+                    nick: player.nick.clone(),
+                    score: words.iter().map(|word| points(word)).sum(),
+                    words,
+                }
+            })
+            .collect();
+
+        players.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.nick.cmp(&b.nick)));
+
+        RoundResults {
+            grid: self.grid,
+            players,
+        }
+    }
+
+    fn nick_in_use(&self, nick: &str) -> bool {
+        self.all_players().any(|player| player.nick == nick)
+    }
+
+    /// The session of `player`, sent once the player is in the game.
+    fn session(player: &Player) -> client::Message {
+        let mut words: Vec<String> = player.found_words.iter().cloned().collect();
+        words.sort();
+
+        client::Message::Session(client::message::Session {
+            token: player.token.clone(),
+            words,
+        })
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        let players = self.players.values()
+            .chain(self.detached.values())
+            .map(|player| PlayerSnapshot {
+                nick: player.nick.clone(),
+                token: player.token.clone(),
+                words: player.found_words.iter().cloned().collect(),
+            })
+            .collect();
+        let bots = self.bots.iter()
+            .map(|(_, player)| (player.nick.clone(), player.found_words.iter().cloned().collect()))
+            .collect();
+
+        Snapshot {
+            grid: self.grid,
+            dictionary: self.dictionary.clone(),
+            next_dictionary: self.next_dictionary.clone(),
+            started: self.started,
+            deadline: self.deadline,
+            players,
+            bots,
+            history: self.history.iter().cloned().collect(),
         }
     }
 
+    /// Continue the game saved in `path`, whose players may resume their sessions.
+    fn restore(&mut self, path: &Path, ctx: &mut <Self as Actor>::Context) -> Result<(), Error> {
+        let snapshot = Snapshot::load(path)?;
+
+        let dict = &self.dicts.get(&snapshot.dictionary)
+            .ok_or_else(|| format_err!("Unknown dictionary '{}'", snapshot.dictionary))?
+            .dict;
+
+        self.grid = snapshot.grid;
+        self.words = self.grid.words(dict).into_iter().collect::<Dict>();
+        self.packed_words = PackedDict::from(&self.words);
+        self.dictionary = snapshot.dictionary;
+        self.started = snapshot.started;
+        self.deadline = snapshot.deadline;
+        self.history = snapshot.history.into_iter().collect();
+
+        if self.dicts.contains(&snapshot.next_dictionary) {
+            self.next_dictionary = snapshot.next_dictionary;
+        }
+
+        for saved in snapshot.players {
+            let mut player = Player::new(saved.nick);
+            player.token = saved.token.clone();
+            player.found_words = saved.words.into_iter().collect();
+            self.detached.insert(saved.token, player);
+        }
+
+        let mut rng = thread_rng();
+
+        for (bot, player) in &mut self.bots {
+            player.found_words = snapshot.bots.iter()
+                .find(|(nick, _)| *nick == player.nick)
+                .map(|(_, words)| words.iter().cloned().collect())
+                .unwrap_or_default();
+            bot.new_game(&self.words, &mut rng);
+            bot.skip_found(&player.found_words);
+        }
+
+        // The log of the round is continued, its file is named after the start
+        if let Some(dir) = &self.settings.replay_dir {
+            match ReplayLog::create(dir, self.started) {
+                Ok(replay) => self.replay = Some(replay),
+                Err(e) => error!(dir = %dir.display(), error = %e, "Failed to reopen replay log"),
+            }
+        }
+
+        let remaining = self.deadline - Utc::now();
+
+        if remaining > chrono::Duration::zero() {
+            METRICS.active_games.set(1);
+        }
+
+        info!(
+            room = ROOM_ID,
+            players = self.detached.len(),
+            deadline = %self.deadline,
+            "Restored game",
+        );
+
+        // A deadline that passed while the server was down ends the round right away
+        ctx.run_later(remaining.to_std().unwrap_or_default(), |_this, ctx| {
+            ctx.notify(EndRound);
+        });
+
+        // Only removed once restored, so a game that can't be restored yet isn't lost
+        if let Err(e) = fs::remove_file(path) {
+            error!(path = %path.display(), error = %e, "Failed to remove restored snapshot");
+        }
+
+        Ok(())
+    }
+
+    fn dictionaries(&self) -> client::Message {
+        use self::client::message::{Dictionaries, DictionaryInfo};
+
+        client::Message::Dictionaries(Dictionaries {
+            dictionaries: self.dicts.entries().map(|entry| DictionaryInfo {
+                id: entry.id.clone(),
+                description: entry.description.clone(),
+            }).collect(),
+            selected: self.next_dictionary.clone(),
+        })
+    }
+
     fn broadcast_found_words(&self, nick: String, found_words: usize) -> Result<(), Error> {
         use self::client::message::PlayerStatus;
 
-        for client in self.players.keys() {
+        for client in self.clients() {
             client.try_send(client::Message::PlayerStatus(PlayerStatus::FoundWords {
                 nick: nick.clone(),
                 count: found_words,
-            }).into()).map_err(|e| format_err!("{}", e))?;
+            }).into()).map_err(|e| {
+                METRICS.send_failures.inc();
+                format_err!("{}", e)
+            })?;
         }
 
         Ok(())
@@ -51,9 +386,24 @@ impl Actor for Server {
     type Context = Context<Self>;
 
     fn started(&mut self, ctx: &mut Self::Context) {
-        ctx.notify(NewGrid);
-        ctx.run_interval(INTERVAL.to_std().unwrap(), |_this, ctx| {
+        let snapshot = self.settings.snapshot.clone().filter(|path| path.exists());
+        let restored = match snapshot {
+            Some(path) => match self.restore(&path, ctx) {
+                Ok(()) => true,
+                Err(e) => {
+                    error!(path = %path.display(), error = %e, "Failed to restore game");
+                    false
+                },
+            },
+            None => false,
+        };
+
+        if !restored {
             ctx.notify(NewGrid);
+        }
+
+        ctx.run_interval(std::time::Duration::from_secs(1), |_this, ctx| {
+            ctx.notify(TickBots);
         });
     }
 }
@@ -62,34 +412,41 @@ impl Handler<NewClient> for Server {
     type Result = Result<(), Error>;
 
     fn handle(&mut self, msg: NewClient, _ctx: &mut <Self as Actor>::Context) -> Result<(), Error> {
-        use self::client::message::*;
         let NewClient { nick, client } = msg;
-        
-        ensure!(!nick.is_empty(), "Empty nick");
 
-        if self.players.values().find(|player| player.nick == nick).is_some() {
-            client.do_send(client::Message::NickAlreadyInUse(NickAlreadyInUse {
-                nick: nick,
-            }).into());
+        if nick.is_empty() {
+            client.do_send(ClientError::new(ErrorCode::EmptyNick, "Nick must not be empty").into());
             return Ok(());
         }
 
-        client.try_send(client::Message::NewGame(NewGame {
-            nick: nick.clone(),
-            grid: self.grid.clone(),
-            words: self.words.clone(),
-            deadline: self.deadline.clone(),
-        }).into()).map_err(|e| format_err!("{}", e))?;
+        if self.nick_in_use(&nick) {
+            client.do_send(ClientError::new(ErrorCode::NickTaken, format!("Nick '{}' is already in use", nick)).into());
+            return Ok(());
+        }
 
-        // Send current word counts to current player
-        for player in self.players.values() {
-            client.do_send(client::Message::PlayerStatus(PlayerStatus::FoundWords {
-                nick: player.nick.clone(),
-                count: player.found_words.len(),
-            }).into());
+        // Players of a restored game keep their place until they resume or the game ends
+        let players = self.players.len() + self.detached.len();
+
+        if self.settings.max_players.map_or(false, |max_players| players >= max_players) {
+            client.do_send(ClientError::new(ErrorCode::RoomFull, "The game is full, try again later").into());
+            return Ok(());
         }
 
-        self.players.insert(client, Player::new(nick.clone()));
+        client.try_send(self.new_game(nick.clone()).into()).map_err(|e| format_err!("{}", e))?;
+        self.send_player_statuses(&client);
+
+        // Spectators may join the game
+        self.spectators.remove(&client);
+        let player = Player::new(nick.clone());
+        client.do_send(Self::session(&player).into());
+        self.players.insert(client, player);
+        info!(room = ROOM_ID, nick = %nick, players = self.players.len(), "Player joined");
+
+        self.log(Event::Joined {
+            time: Utc::now(),
+            nick: nick.clone(),
+            bot: false,
+        });
 
         self.broadcast_found_words(nick, 0)?;
 
@@ -97,24 +454,262 @@ impl Handler<NewClient> for Server {
     }
 }
 
+impl Handler<Spectate> for Server {
+    type Result = ();
+
+    fn handle(&mut self, msg: Spectate, _ctx: &mut <Self as Actor>::Context) {
+        let Spectate { client } = msg;
+
+        if self.players.contains_key(&client) {
+            client.do_send(ClientError::new(ErrorCode::NotAllowed, "Players can't become spectators").into());
+            return;
+        }
+
+        client.do_send(self.new_game(String::new()).into());
+        self.send_player_statuses(&client);
+        self.spectators.insert(client);
+        info!(room = ROOM_ID, spectators = self.spectators.len(), "Spectator joined");
+    }
+}
+
+impl Handler<Resume> for Server {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: Resume, _ctx: &mut <Self as Actor>::Context) -> Result<(), Error> {
+        let Resume { client, token } = msg;
+
+        if self.players.contains_key(&client) {
+            client.do_send(ClientError::new(ErrorCode::NotAllowed, "Already playing").into());
+            return Ok(());
+        }
+
+        let player = match self.detached.remove(&token) {
+            Some(player) => player,
+            None => {
+                client.do_send(ClientError::new(ErrorCode::UnknownSession, "The session has ended, log in again").into());
+                return Ok(());
+            },
+        };
+
+        // Only possible if `max_players` was lowered while the server was down
+        if self.settings.max_players.map_or(false, |max_players| self.players.len() >= max_players) {
+            self.detached.insert(token, player);
+            client.do_send(ClientError::new(ErrorCode::RoomFull, "The game is full, try again later").into());
+            return Ok(());
+        }
+
+        let nick = player.nick.clone();
+        let found_words = player.found_words.len();
+
+        client.try_send(self.new_game(nick.clone()).into()).map_err(|e| format_err!("{}", e))?;
+        self.send_player_statuses(&client);
+        client.do_send(Self::session(&player).into());
+
+        // The replay log still has the player, so there is no `Joined` event
+        self.spectators.remove(&client);
+        self.players.insert(client, player);
+        info!(room = ROOM_ID, nick = %nick, players = self.players.len(), "Player resumed");
+
+        self.broadcast_found_words(nick, found_words)?;
+
+        Ok(())
+    }
+}
+
+impl Handler<Shutdown> for Server {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Shutdown, _ctx: &mut <Self as Actor>::Context) {
+        let mut saved = false;
+
+        if let Some(path) = &self.settings.snapshot {
+            if Utc::now() < self.deadline {
+                match self.snapshot().save(path) {
+                    Ok(()) => {
+                        info!(path = %path.display(), "Saved game");
+                        saved = true;
+                    },
+                    Err(e) => error!(path = %path.display(), error = %e, "Failed to save game"),
+                }
+            }
+        }
+
+        let message = if saved {
+            "The server is restarting, the game continues once it is back"
+        } else {
+            "The server is shutting down"
+        };
+
+        for client in self.clients() {
+            client.do_send(client::Message::Shutdown(client::message::Shutdown {
+                message: message.into(),
+            }).into());
+        }
+
+        // Players are saved, not leaving, so their `Left` events must not be logged
+        self.players.clear();
+        self.spectators.clear();
+        self.replay = None;
+    }
+}
+
 impl Handler<NewGrid> for Server {
     type Result = ();
 
-    fn handle(&mut self, _msg: NewGrid, _ctx: &mut <Self as Actor>::Context) {
-        use self::client::message::NewGame;
+    fn handle(&mut self, _msg: NewGrid, ctx: &mut <Self as Actor>::Context) {
+        if let Some(dicts) = self.reloaded_dicts.take() {
+            self.dicts = dicts;
+
+            if !self.dicts.contains(&self.next_dictionary) {
+                self.next_dictionary = self.settings.dictionary.clone();
+            }
+
+            for client in self.clients() {
+                client.do_send(self.dictionaries().into());
+            }
+        }
+
+        self.dictionary = self.next_dictionary.clone();
+        let dict = &self.dicts.get(&self.dictionary)
+            .expect("selected dictionary missing from registry")
+            .dict;
 
-        self.deadline = Utc::now() + *INTERVAL;
-        self.grid = thread_rng().gen::<Grid>();
-        self.words = self.grid.words(&DICT).into_iter().collect::<Dict>();
+        let mut rng = thread_rng();
+
+        self.started = Utc::now();
+        self.deadline = self.started + self.settings.round;
+        self.grid = match &self.settings.dice {
+            Some(dice) => rng.sample(dice),
+            None => rng.sample(Standard),
+        };
+        let solving = std::time::Instant::now();
+        self.words = self.grid.words(dict).into_iter().collect::<Dict>();
+        METRICS.solver.observe(solving.elapsed());
+        self.packed_words = PackedDict::from(&self.words);
 
-        for (client, player) in &mut self.players {
+        for (bot, player) in &mut self.bots {
             player.found_words.clear();
-            client.do_send(client::Message::NewGame(NewGame {
-                nick: player.nick.clone(),
-                grid: self.grid.clone(),
-                words: self.words.clone(),
-                deadline: self.deadline.clone(),
-            }).into());
+            bot.new_game(&self.words, &mut rng);
+        }
+
+        for player in self.players.values_mut() {
+            player.found_words.clear();
+            player.invalid_words = 0;
+        }
+
+        for (client, player) in &self.players {
+            client.do_send(self.new_game(player.nick.clone()).into());
+        }
+
+        for client in &self.spectators {
+            client.do_send(self.new_game(String::new()).into());
+        }
+
+        self.start_replay();
+        METRICS.active_games.set(1);
+
+        info!(
+            room = ROOM_ID,
+            dictionary = %self.dictionary,
+            words = self.words.words().count(),
+            deadline = %self.deadline,
+            "Round started",
+        );
+
+        ctx.run_later(self.settings.round.to_std().unwrap_or_default(), |_this, ctx| {
+            ctx.notify(EndRound);
+        });
+    }
+}
+
+impl Handler<EndRound> for Server {
+    type Result = ();
+
+    fn handle(&mut self, _msg: EndRound, ctx: &mut <Self as Actor>::Context) {
+        let results = self.round_results();
+
+        METRICS.active_games.set(0);
+        METRICS.rounds_played.inc();
+
+        info!(
+            room = ROOM_ID,
+            players = results.players.len(),
+            winner = results.players.first().map_or("", |player| &*player.nick),
+            "Round ended",
+        );
+
+        if self.settings.reveal_results {
+            for client in &self.spectators {
+                client.do_send(client::Message::RoundResults(results.clone()).into());
+            }
+        }
+
+        let now = Utc::now();
+
+        self.log(Event::RoundEnded {
+            time: now,
+            results: results.players.clone(),
+        });
+        self.replay = None;
+
+        // Sessions only outlive connections until the end of the game
+        self.detached.clear();
+
+        // Only words that were just revealed are defined, so `Define` can't check words during a round
+        self.definitions = match self.dicts.get(&self.dictionary).and_then(|entry| entry.definitions.as_ref()) {
+            Some(definitions) => self.words.words()
+                .filter_map(|word| definitions.get(word).map(|definition| (word.to_owned(), definition.to_owned())))
+                .collect(),
+            None => HashMap::new(),
+        };
+
+        self.history.push_back(api::Round {
+            started: self.started,
+            ended: now,
+            dictionary: self.dictionary.clone(),
+            results,
+        });
+
+        while self.history.len() > self.settings.history {
+            self.history.pop_front();
+        }
+
+        ctx.run_later(self.settings.intermission.to_std().unwrap_or_default(), |_this, ctx| {
+            ctx.notify(NewGrid);
+        });
+    }
+}
+
+impl Handler<TickBots> for Server {
+    type Result = ();
+
+    fn handle(&mut self, _msg: TickBots, _ctx: &mut <Self as Actor>::Context) {
+        if Utc::now() >= self.deadline {
+            return;
+        }
+
+        let mut rng = thread_rng();
+        let mut found = Vec::new();
+
+        for (bot, player) in &mut self.bots {
+            if let Some(word) = bot.tick(&mut rng) {
+                if player.found_words.insert(word.clone()) {
+                    found.push((player.nick.clone(), word, player.found_words.len()));
+                }
+            }
+        }
+
+        for (nick, word, found_words) in found {
+            self.log(Event::Submitted {
+                time: Utc::now(),
+                nick: nick.clone(),
+                word,
+                verdict: Verdict::Accepted,
+            });
+
+            if let Err(e) = self.broadcast_found_words(nick, found_words) {
+                error!(error = %e, "Failed to broadcast found words of bot");
+            }
         }
     }
 }
@@ -125,23 +720,55 @@ impl Handler<SubmitWord> for Server {
     fn handle(&mut self, msg: SubmitWord, _ctx: &mut <Self as Actor>::Context) -> Result<(), Error> {
         let SubmitWord { client, word } = msg;
 
-        if self.words.values().find(|found_word| **found_word == word).is_none() {
+        // Words don't count during the intermission
+        if Utc::now() >= self.deadline {
             return Ok(());
         }
 
-        let player = self.players.get_mut(&client)
-            .ok_or_else(|| format_err!("Player not found"))?;
+        let on_grid = self.words.values().any(|grid_word| *grid_word == word);
 
-        if player.found_words.contains(&word) {
+        let player = match self.players.get_mut(&client) {
+            Some(player) => player,
+            None if self.spectators.contains(&client) => {
+                client.do_send(ClientError::new(ErrorCode::NotAllowed, "Spectators can't submit words").into());
+                return Ok(());
+            },
+            None => {
+                client.do_send(ClientError::new(ErrorCode::NotAllowed, "Log in before submitting words").into());
+                return Ok(());
+            },
+        };
+
+        if player.invalid_words >= self.settings.max_invalid_words {
+            client.do_send(ClientError::new(ErrorCode::RateLimited, "Too many invalid words this round").into());
             return Ok(());
         }
 
-        player.found_words.insert(word);
+        let verdict = if !on_grid {
+            player.invalid_words += 1;
+            Verdict::Invalid
+        } else if !player.found_words.insert(word.clone()) {
+            Verdict::AlreadyFound
+        } else {
+            Verdict::Accepted
+        };
 
         let nick = player.nick.clone();
         let found_words = player.found_words.len();
 
-        println!("Broadcasting found words: ({}) {}", found_words, player.nick);
+        METRICS.submitted(verdict);
+        self.log(Event::Submitted {
+            time: Utc::now(),
+            nick: nick.clone(),
+            word,
+            verdict,
+        });
+
+        if verdict != Verdict::Accepted {
+            return Ok(());
+        }
+
+        debug!(room = ROOM_ID, nick = %nick, found_words, "Broadcasting found words");
 
         self.broadcast_found_words(nick, found_words)?;
 
@@ -149,6 +776,185 @@ impl Handler<SubmitWord> for Server {
     }
 }
 
+impl Handler<ListDictionaries> for Server {
+    type Result = ();
+
+    fn handle(&mut self, msg: ListDictionaries, _ctx: &mut <Self as Actor>::Context) {
+        msg.client.do_send(self.dictionaries().into());
+    }
+}
+
+impl Handler<SelectDictionary> for Server {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: SelectDictionary, _ctx: &mut <Self as Actor>::Context) -> Result<(), Error> {
+        let SelectDictionary { client, id } = msg;
+
+        if !self.players.contains_key(&client) {
+            client.do_send(ClientError::new(ErrorCode::NotAllowed, "Log in before selecting a dictionary").into());
+            return Ok(());
+        }
+
+        if !self.dicts.contains(&id) {
+            client.do_send(ClientError::new(ErrorCode::UnknownDictionary, format!("Unknown dictionary '{}'", id)).into());
+            return Ok(());
+        }
+
+        self.next_dictionary = id;
+
+        for client in self.clients() {
+            client.do_send(self.dictionaries().into());
+        }
+
+        Ok(())
+    }
+}
+
+impl Handler<Chat> for Server {
+    type Result = ();
+
+    fn handle(&mut self, msg: Chat, _ctx: &mut <Self as Actor>::Context) {
+        let Chat { client, text } = msg;
+        let text: String = text.chars().filter(|c| !c.is_control()).collect();
+        let text = text.trim();
+
+        // Also drops messages of nothing but control characters
+        if text.is_empty() {
+            return;
+        }
+
+        if !self.settings.chat_during_play && Utc::now() < self.deadline {
+            client.do_send(ClientError::new(ErrorCode::ChatDisabled, "Chat is disabled until the game is over").into());
+            return;
+        }
+
+        if text.chars().count() > MAX_CHAT_LENGTH {
+            let message = format!("Chat messages may be at most {} characters long", MAX_CHAT_LENGTH);
+            client.do_send(ClientError::new(ErrorCode::ChatTooLong, message).into());
+            return;
+        }
+
+        let player = match self.players.get(&client) {
+            Some(player) => player,
+            None => {
+                client.do_send(ClientError::new(ErrorCode::NotAllowed, "Log in before chatting").into());
+                return;
+            },
+        };
+
+        let chat = client::message::Chat {
+            nick: player.nick.clone(),
+            text: text.into(),
+        };
+
+        for client in self.clients() {
+            client.do_send(client::Message::Chat(chat.clone()).into());
+        }
+    }
+}
+
+impl Handler<Define> for Server {
+    type Result = ();
+
+    fn handle(&mut self, msg: Define, _ctx: &mut <Self as Actor>::Context) {
+        use self::client::message::Definition;
+
+        let Define { client, word } = msg;
+        let definition = self.definitions.get(&word).cloned();
+
+        client.do_send(client::Message::Definition(Definition {
+            word,
+            definition,
+        }).into());
+    }
+}
+
+impl Handler<ReloadDictionaries> for Server {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: ReloadDictionaries, _ctx: &mut <Self as Actor>::Context) -> Result<(), Error> {
+        let ReloadDictionaries { dicts } = msg;
+
+        ensure!(dicts.contains(&self.settings.dictionary), "Default dictionary '{}' is missing", self.settings.dictionary);
+
+        // Swapping is deferred to the next game, so the current one stays consistent
+        self.reloaded_dicts = Some(dicts);
+
+        Ok(())
+    }
+}
+
+impl Handler<GetRooms> for Server {
+    type Result = MessageResult<GetRooms>;
+
+    fn handle(&mut self, _msg: GetRooms, _ctx: &mut <Self as Actor>::Context) -> Self::Result {
+        MessageResult(vec![api::Room {
+            id: ROOM_ID.into(),
+            grid: self.grid,
+            dictionary: self.dictionary.clone(),
+            started: self.started,
+            deadline: self.deadline,
+            players: self.scores(),
+            spectators: self.spectators.len(),
+        }])
+    }
+}
+
+impl Handler<GetScores> for Server {
+    type Result = MessageResult<GetScores>;
+
+    fn handle(&mut self, _msg: GetScores, _ctx: &mut <Self as Actor>::Context) -> Self::Result {
+        MessageResult(self.scores())
+    }
+}
+
+impl Handler<GetHistory> for Server {
+    type Result = MessageResult<GetHistory>;
+
+    fn handle(&mut self, msg: GetHistory, _ctx: &mut <Self as Actor>::Context) -> Self::Result {
+        MessageResult(self.history.iter().rev().take(msg.limit).cloned().collect())
+    }
+}
+
+impl Handler<GetLeaderboard> for Server {
+    type Result = MessageResult<GetLeaderboard>;
+
+    fn handle(&mut self, _msg: GetLeaderboard, _ctx: &mut <Self as Actor>::Context) -> Self::Result {
+        MessageResult(api::leaderboard(&self.history))
+    }
+}
+
+impl Handler<GetUptime> for Server {
+    type Result = MessageResult<GetUptime>;
+
+    fn handle(&mut self, _msg: GetUptime, _ctx: &mut <Self as Actor>::Context) -> Self::Result {
+        MessageResult(Utc::now() - self.launched)
+    }
+}
+
+impl Handler<Ban> for Server {
+    type Result = ();
+
+    fn handle(&mut self, msg: Ban, _ctx: &mut <Self as Actor>::Context) {
+        let Ban { peer, until } = msg;
+
+        warn!(peer = %peer, until = %until, "Banned");
+        METRICS.bans.inc();
+        self.bans.insert(peer, until);
+    }
+}
+
+impl Handler<IsBanned> for Server {
+    type Result = bool;
+
+    fn handle(&mut self, msg: IsBanned, _ctx: &mut <Self as Actor>::Context) -> bool {
+        let now = Utc::now();
+
+        self.bans.retain(|_, until| *until > now);
+        self.bans.contains_key(&msg.peer)
+    }
+}
+
 impl Handler<Disconnected> for Server {
     type Result = Result<(), Error>;
 
@@ -156,12 +962,25 @@ impl Handler<Disconnected> for Server {
         use self::client::message::PlayerStatus;
 
         let Disconnected { client } = msg;
+
+        if self.spectators.remove(&client) {
+            info!(room = ROOM_ID, spectators = self.spectators.len(), "Spectator left");
+            return Ok(());
+        }
+
         let player = match self.players.remove(&client) {
             Some(player) => player,
             None => return Ok(()),
         };
 
-        for client in self.players.keys() {
+        info!(room = ROOM_ID, nick = %player.nick, players = self.players.len(), "Player left");
+
+        self.log(Event::Left {
+            time: Utc::now(),
+            nick: player.nick.clone(),
+        });
+
+        for client in self.clients() {
             client.do_send(client::Message::PlayerStatus(PlayerStatus::Disconnected {
                 nick: player.nick.clone(),
             }).into());
@@ -173,14 +992,22 @@ impl Handler<Disconnected> for Server {
 
 struct Player {
     nick: String,
+    /// Allows resuming the game after the server restarted
+    token: String,
     found_words: HashSet<String>,
+    /// Words submitted in this round that aren't on the grid
+    invalid_words: usize,
 }
 
 impl Player {
     fn new(nick: String) -> Self {
+        let token = thread_rng().sample_iter(Alphanumeric).take(32).collect();
+
         Self {
             nick,
+            token,
             found_words: HashSet::new(),
+            invalid_words: 0,
         }
     }
 }
@@ -191,6 +1018,27 @@ impl Message for NewGrid {
     type Result = ();
 }
 
+/// Sent when the deadline of a round passes
+struct EndRound;
+
+impl Message for EndRound {
+    type Result = ();
+}
+
+struct TickBots;
+
+impl Message for TickBots {
+    type Result = ();
+}
+
+struct Spectate {
+    client: Addr<Client>,
+}
+
+impl Message for Spectate {
+    type Result = ();
+}
+
 struct NewClient {
     client: Addr<Client>,
     nick: String,
@@ -209,6 +1057,123 @@ impl Message for SubmitWord {
     type Result = Result<(), Error>;
 }
 
+struct ListDictionaries {
+    client: Addr<Client>,
+}
+
+impl Message for ListDictionaries {
+    type Result = ();
+}
+
+struct SelectDictionary {
+    client: Addr<Client>,
+    id: String,
+}
+
+impl Message for SelectDictionary {
+    type Result = Result<(), Error>;
+}
+
+struct Define {
+    client: Addr<Client>,
+    word: String,
+}
+
+impl Message for Define {
+    type Result = ();
+}
+
+struct Chat {
+    client: Addr<Client>,
+    text: String,
+}
+
+impl Message for Chat {
+    type Result = ();
+}
+
+/// Replace the dictionaries of the server, starting with the next game.
+pub struct ReloadDictionaries {
+    pub dicts: Registry,
+}
+
+impl Message for ReloadDictionaries {
+    type Result = Result<(), Error>;
+}
+
+/// Id of the only room, the server runs a single game.
+const ROOM_ID: &str = "main";
+
+/// All games, see `http`.
+struct GetRooms;
+
+impl Message for GetRooms {
+    type Result = Vec<api::Room>;
+}
+
+struct GetScores;
+
+impl Message for GetScores {
+    type Result = Vec<api::Score>;
+}
+
+/// The most recent ended rounds, newest first.
+struct GetHistory {
+    limit: usize,
+}
+
+impl Message for GetHistory {
+    type Result = Vec<api::Round>;
+}
+
+struct GetLeaderboard;
+
+impl Message for GetLeaderboard {
+    type Result = Vec<api::LeaderboardEntry>;
+}
+
+/// Time since the server was created.
+struct GetUptime;
+
+impl Message for GetUptime {
+    type Result = chrono::Duration;
+}
+
+struct Resume {
+    client: Addr<Client>,
+    token: String,
+}
+
+impl Message for Resume {
+    type Result = Result<(), Error>;
+}
+
+/// Save the game if one is in progress and disconnect everybody.
+pub struct Shutdown;
+
+impl Message for Shutdown {
+    type Result = ();
+}
+
+/// Refuse connections from `peer` until `until`.
+struct Ban {
+    peer: String,
+    until: DateTime<Utc>,
+}
+
+impl Message for Ban {
+    type Result = ();
+}
+
+/// Whether connections from `peer` are refused.
+struct IsBanned {
+    peer: String,
+}
+
+impl Message for IsBanned {
+    type Result = bool;
+}
+
 struct Disconnected {
     client: Addr<Client>,
 }
@@ -219,20 +1184,71 @@ impl Message for Disconnected {
 
 pub struct Client {
     server: Addr<Server>,
+    /// Context of everything logged about this connection
+    span: tracing::Span,
+    /// Protocol version agreed on in the handshake
+    protocol_version: Option<u32>,
+    /// Codec chosen by the frame type of the handshake
+    codec: Codec,
+    /// Address of the client, `None` if unknown
+    peer: Option<String>,
+    limits: Limits,
+    submissions: Bucket,
+    logins: Bucket,
+    chat: Bucket,
+    /// Rate limit violations so far
+    violations: u32,
 }
 
 impl Client {
-    pub fn new(server: Addr<Server>) -> Self {
+    /// Create the actor of a connection from `peer`.
+    pub fn new(server: Addr<Server>, peer: Option<String>, limits: Limits) -> Self {
+        let span = info_span!(
+            "connection",
+            peer = peer.as_deref().unwrap_or("unknown"),
+            room = ROOM_ID,
+            nick = tracing::field::Empty,
+        );
+
         Self {
             server,
+            span,
+            protocol_version: None,
+            codec: Codec::default(),
+            peer,
+            submissions: Bucket::new(limits.submissions),
+            logins: Bucket::new(limits.logins),
+            chat: Bucket::new(limits.chat),
+            limits,
+            violations: 0,
+        }
+    }
+
+    fn send(&self, msg: client::Message, ctx: &mut <Self as Actor>::Context) -> Result<(), Error> {
+        let data = msg.to_vec_with(self.codec)?;
+
+        if self.codec.is_text() {
+            ctx.text(String::from_utf8(data)?);
+        } else {
+            ctx.binary(data);
         }
+
+        Ok(())
     }
 }
 
 impl Actor for Client {
     type Context = ws::WebsocketContext<Self>;
 
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        METRICS.connected_clients.inc();
+        self.span.in_scope(|| info!("Connected"));
+    }
+
     fn stopped(&mut self, ctx: &mut Self::Context) {
+        METRICS.connected_clients.dec();
+        self.span.in_scope(|| info!("Disconnected"));
+
         self.server.do_send(Disconnected {
             client: ctx.address(),
         });
@@ -251,48 +1267,114 @@ impl From<client::Message> for ClientMessage {
     }
 }
 
+impl From<ClientError> for ClientMessage {
+    fn from(error: ClientError) -> ClientMessage {
+        ClientMessage(error.into())
+    }
+}
+
 impl Handler<ClientMessage> for Client {
     type Result = Result<(), Error>;
 
     fn handle(&mut self, msg: ClientMessage, ctx: &mut Self::Context) -> Self::Result {
-        let msg = msg.0.to_vec()?;
-        ctx.binary(msg);
-        Ok(())
+        let span = self.span.clone();
+        let _entered = span.enter();
+
+        // The server accepted the login
+        if let client::Message::NewGame(ref new_game) = msg.0 {
+            if !new_game.nick.is_empty() {
+                self.span.record("nick", &&*new_game.nick);
+            }
+        }
+
+        if let client::Message::Error { code, ref message } = msg.0 {
+            self.fail(ClientError::new(code, message.clone()), ctx);
+            return Ok(());
+        }
+
+        if let client::Message::Shutdown(_) = msg.0 {
+            self.send(msg.0, ctx)?;
+            ctx.close(Some(ws::CloseCode::Away.into()));
+            ctx.stop();
+            return Ok(());
+        }
+
+        self.send(msg.0, ctx)
     }
 }
 
 impl StreamHandler<Result<ws::Message, ProtocolError>> for Client {
     fn handle(&mut self, msg: Result<ws::Message, ProtocolError>, ctx: &mut Self::Context) {
+        let span = self.span.clone();
+        let _entered = span.enter();
+
         let msg = match msg {
             Ok(msg) => msg,
             Err(err) => {
-                eprintln!("Error: {}", err);
-                ctx.close(None);
+                self.fail(ClientError::new(ErrorCode::MalformedMessage, err.to_string()), ctx);
                 return;
             }
         };
 
         let res = match msg {
             ws::Message::Ping(msg) => Ok(ctx.pong(&msg)),
-            ws::Message::Binary(msg) => self.on_message(msg.as_ref(), ctx),
-            ws::Message::Text(text) => self.on_message(text.as_bytes(), ctx),
+            ws::Message::Binary(msg) => self.on_message(Codec::Bincode, msg.as_ref(), ctx),
+            ws::Message::Text(text) => self.on_message(Codec::Json, text.as_bytes(), ctx),
             ws::Message::Close(reason) => self.on_close(reason, ctx),
             _ => Ok(()),
         };
 
         if let Err(e) = res {
-            eprintln!("{}", e);
-            eprintln!("TODO: Disconnect cleanup");
-            ctx.close(None);
+            let error = match e.downcast::<ClientError>() {
+                Ok(error) => error,
+                Err(e) => {
+                    error!(error = %e, "Failed to handle message");
+                    ClientError::internal()
+                },
+            };
+
+            self.fail(error, ctx);
         }
     }
 }
 
 impl Client {
-    fn on_message(&mut self, msg: &[u8], ctx: &mut <Self as Actor>::Context) -> Result<(), Error> {
-        let msg = server::Message::from_slice(&msg)?;
+    fn on_message(&mut self, codec: Codec, msg: &[u8], ctx: &mut <Self as Actor>::Context) -> Result<(), Error> {
+        if self.protocol_version.is_none() {
+            // Reply in whatever encoding the client started with
+            self.codec = codec;
+        }
+
+        if codec != self.codec {
+            let message = format!("Expected {:?} frames after the handshake, got {:?}", self.codec, codec);
+            return Err(ClientError::new(ErrorCode::MalformedMessage, message).into());
+        }
+
+        let msg = server::Message::from_slice_with(codec, msg)
+            .map_err(|e| {
+                METRICS.decode_errors.inc();
+                ClientError::new(ErrorCode::MalformedMessage, e.to_string())
+            })?;
+
+        if self.protocol_version.is_none() {
+            return self.on_hello(msg, ctx);
+        }
+
+        let bucket = match msg {
+            server::Message::Login(_) | server::Message::Resume(_) => Some(&mut self.logins),
+            server::Message::SubmitWord(_) => Some(&mut self.submissions),
+            server::Message::Chat(_) => Some(&mut self.chat),
+            _ => None,
+        };
+
+        if !bucket.map_or(true, Bucket::take) {
+            return Err(ClientError::new(ErrorCode::RateLimited, "Too many messages, slow down").into());
+        }
 
         match msg {
+            server::Message::Hello(_) => {
+                return Err(ClientError::new(ErrorCode::UnexpectedMessage, "Repeated handshake").into());
+            },
             server::Message::Login(login) => self.server.do_send(NewClient {
                 client: ctx.address(),
                 nick: login.nick,
@@ -301,11 +1383,122 @@ impl Client {
                 client: ctx.address(),
                 word: submit_word.word,
             }),
+            server::Message::ListDictionaries => self.server.do_send(ListDictionaries {
+                client: ctx.address(),
+            }),
+            server::Message::SelectDictionary(select) => self.server.do_send(SelectDictionary {
+                client: ctx.address(),
+                id: select.id,
+            }),
+            server::Message::Define(define) => self.server.do_send(Define {
+                client: ctx.address(),
+                word: define.word,
+            }),
+            server::Message::Chat(chat) => self.server.do_send(Chat {
+                client: ctx.address(),
+                text: chat.text,
+            }),
+            server::Message::Spectate => self.server.do_send(Spectate {
+                client: ctx.address(),
+            }),
+            server::Message::Resume(resume) => self.server.do_send(Resume {
+                client: ctx.address(),
+                token: resume.token,
+            }),
         }
 
         Ok(())
     }
 
+    fn on_hello(&mut self, msg: server::Message, ctx: &mut <Self as Actor>::Context) -> Result<(), Error> {
+        use self::client::message::{Welcome, Incompatible};
+
+        let reason = match msg {
+            server::Message::Hello(ref hello) if boggle_common::is_compatible(hello.protocol_version) => {
+                self.protocol_version = Some(hello.protocol_version);
+
+                return self.send(client::Message::Welcome(Welcome {
+                    protocol_version: PROTOCOL_VERSION,
+                    capabilities: vec![
+                        capability::DICTIONARIES.into(),
+                        capability::DEFINITIONS.into(),
+                        capability::CHAT.into(),
+                        capability::RESUME.into(),
+                    ],
+                }), ctx);
+            },
+            server::Message::Hello(hello) => format!(
+                "Protocol version {} is not supported, the server supports versions {} to {}",
+                hello.protocol_version, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION,
+            ),
+            _ => "Expected a Hello message to start the handshake".into(),
+        };
+
+        self.send(client::Message::Incompatible(Incompatible {
+            protocol_version: PROTOCOL_VERSION,
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            message: reason.clone(),
+        }), ctx)?;
+
+        ctx.close(Some(ws::CloseReason {
+            code: ws::CloseCode::Protocol,
+            description: Some(reason),
+        }));
+        ctx.stop();
+
+        Ok(())
+    }
+
+    /// Report an error to the client, closing the connection if it is fatal.
+    fn fail(&mut self, mut error: ClientError, ctx: &mut <Self as Actor>::Context) {
+        if error.code == ErrorCode::RateLimited {
+            let banned = self.violated();
+
+            if banned {
+                error = ClientError::new(ErrorCode::Banned, "Too many messages, disconnecting");
+            }
+        }
+
+        let reason = error.close_reason();
+        let fatal = error.code.is_fatal();
+
+        if fatal {
+            warn!(code = ?error.code, message = %error.message, "Closing connection");
+        } else {
+            debug!(code = ?error.code, message = %error.message, "Reporting error");
+        }
+
+        if let Err(e) = self.send(error.into(), ctx) {
+            error!(error = %e, "Failed to send error");
+        }
+
+        if fatal {
+            ctx.close(Some(reason));
+            ctx.stop();
+        }
+    }
+
+    /// Count a rate limit violation, returning whether the client has to go.
+    fn violated(&mut self) -> bool {
+        METRICS.rate_limited.inc();
+        self.violations += 1;
+
+        if self.limits.max_violations == 0 || self.violations < self.limits.max_violations {
+            return false;
+        }
+
+        if let Some(peer) = &self.peer {
+            if self.limits.ban > chrono::Duration::zero() {
+                self.server.do_send(Ban {
+                    peer: peer.clone(),
+                    until: Utc::now() + self.limits.ban,
+                });
+            }
+        }
+
+        true
+    }
+
     fn on_close(&mut self, _reason: Option<ws::CloseReason>, ctx: &mut <Self as Actor>::Context) -> Result<(), Error> {
         ctx.stop();
         Ok(())
@@ -0,0 +1,128 @@
+use chrono::Duration;
+use serde::Deserialize;
+use std::time::Instant;
+
+/// Messages a connection may send: `burst` at once, refilled by `per_second`.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Rate {
+    pub burst: u32,
+    pub per_second: f64,
+}
+
+/// Abuse protection of a single connection.
+#[derive(Debug, Clone)]
+pub struct Limits {
+    pub submissions: Rate,
+    /// `Login` and `Resume`
+    pub logins: Rate,
+    pub chat: Rate,
+    /// Rate limit violations after which the connection is closed, never if zero
+    pub max_violations: u32,
+    /// How long the address of a closed connection can't reconnect, not at all if zero
+    pub ban: Duration,
+    /// Largest accepted websocket frame, in bytes
+    pub max_frame_size: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            submissions: Rate { burst: 10, per_second: 3.0 },
+            logins: Rate { burst: 3, per_second: 0.2 },
+            chat: Rate { burst: 5, per_second: 0.5 },
+            max_violations: 20,
+            ban: Duration::minutes(5),
+            max_frame_size: 4096,
+        }
+    }
+}
+
+/// Token bucket enforcing a `Rate`.
+#[derive(Debug)]
+pub struct Bucket {
+    rate: Rate,
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl Bucket {
+    /// A full bucket
+    pub fn new(rate: Rate) -> Self {
+        Self {
+            rate,
+            tokens: rate.burst as f64,
+            refilled: Instant::now(),
+        }
+    }
+
+    /// Take a token, returning whether one was left.
+    pub fn take(&mut self) -> bool {
+        self.take_at(Instant::now())
+    }
+
+    fn take_at(&mut self, now: Instant) -> bool {
+        let elapsed = now.duration_since(self.refilled).as_secs_f64();
+
+        self.tokens = (self.tokens + elapsed * self.rate.per_second).min(self.rate.burst as f64);
+        self.refilled = now;
+
+        if self.tokens < 1.0 {
+            return false;
+        }
+
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::*;
+
+    const RATE: Rate = Rate { burst: 3, per_second: 2.0 };
+
+    #[test]
+    fn burst() {
+        let mut bucket = Bucket::new(RATE);
+        let now = bucket.refilled;
+
+        assert!(bucket.take_at(now));
+        assert!(bucket.take_at(now));
+        assert!(bucket.take_at(now));
+        assert!(!bucket.take_at(now));
+    }
+
+    #[test]
+    fn refill() {
+        let mut bucket = Bucket::new(RATE);
+        let start = bucket.refilled;
+
+        for _ in 0..3 {
+            assert!(bucket.take_at(start));
+        }
+
+        // Half a token
+        assert!(!bucket.take_at(start + Duration::from_millis(250)));
+        // Another half
+        assert!(bucket.take_at(start + Duration::from_millis(500)));
+        assert!(!bucket.take_at(start + Duration::from_millis(500)));
+        // Two more
+        assert!(bucket.take_at(start + Duration::from_millis(1500)));
+        assert!(bucket.take_at(start + Duration::from_millis(1500)));
+        assert!(!bucket.take_at(start + Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn refill_is_capped_at_burst() {
+        let mut bucket = Bucket::new(RATE);
+        let later = bucket.refilled + Duration::from_secs(60);
+
+        for _ in 0..3 {
+            assert!(bucket.take_at(later));
+        }
+
+        assert!(!bucket.take_at(later));
+    }
+}
@@ -1,39 +1,311 @@
 use actix::prelude::*;
-use actix_web::{HttpServer, App, web, HttpRequest};
-use actix_web_actors::ws;
-use boggle_server::{Server, Client};
+use actix_web::{HttpServer, App};
+use actix_files::Files;
+use boggle_server::{Server, Shutdown, Bot, http, websocket, admin};
+use boggle_server::admin::Admin;
+use boggle_server::websocket::TrustedProxies;
+use boggle_server::metrics::METRICS;
+use failure::Error;
+use tracing::{info, warn, error};
 use structopt::StructOpt;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Arc;
+use std::time::Instant;
+use dict::Registry;
+use tracing_subscriber::EnvFilter;
 
+mod config;
+use self::config::{Config, DictionariesConfig};
+
+/// Options given here override the ones of the config file.
 #[derive(StructOpt, Debug)]
 struct Opt {
-    #[structopt(short = "h", long = "host", default_value = "localhost")]
-    host: String,
-    #[structopt(short = "p", long = "port", default_value = "8001")]
-    port: u16,
+    /// TOML config file, see `config.example.toml`
+    #[structopt(short = "c", long = "config", parse(from_os_str))]
+    config: Option<PathBuf>,
+    #[structopt(short = "h", long = "host")]
+    host: Option<String>,
+    #[structopt(short = "p", long = "port")]
+    port: Option<u16>,
+    /// Directory with the built web UI to serve next to the websocket
+    #[structopt(long = "static-dir", parse(from_os_str))]
+    static_dir: Option<PathBuf>,
+    /// Directory to load additional packed dictionaries (`<id>.dict`) from
+    #[structopt(long = "dict-dir", parse(from_os_str))]
+    dict_dir: Option<PathBuf>,
+    /// Packed dictionary (`.dict`) or plain text word list to load, may be repeated
+    #[structopt(long = "dict", parse(from_os_str), number_of_values = 1)]
+    dicts: Vec<PathBuf>,
+    /// Id of the dictionary used for games unless players select another one
+    #[structopt(long = "default-dict")]
+    default_dict: Option<String>,
+    /// Seconds players have to find words
+    #[structopt(long = "round")]
+    round: Option<u64>,
+    /// Seconds between the end of a round and the next grid
+    #[structopt(long = "intermission")]
+    intermission: Option<u64>,
+    /// Letter source: standard, classic, revised or sixteen custom dice
+    #[structopt(long = "dice")]
+    dice: Option<String>,
+    /// Number of players that may join, bots and spectators don't count
+    #[structopt(long = "max-players")]
+    max_players: Option<usize>,
+    /// Number of computer players that join every game
+    #[structopt(long = "bots")]
+    bots: Option<usize>,
+    /// Skill of the computer players: easy, medium or hard
+    #[structopt(long = "bot-skill")]
+    bot_skill: Option<String>,
+    /// Only allow chatting after a game has ended
+    #[structopt(long = "no-chat-during-play")]
+    no_chat_during_play: bool,
+    /// Show spectators the words of every player when a round ends
+    #[structopt(long = "reveal-results")]
+    reveal_results: bool,
+    /// Directory to write a replay log of every round to
+    #[structopt(long = "replay-dir", parse(from_os_str))]
+    replay_dir: Option<PathBuf>,
+    /// File to save the game to on shutdown and restore it from on start
+    #[structopt(long = "snapshot", parse(from_os_str))]
+    snapshot: Option<PathBuf>,
+    /// off, error, warn, info, debug, trace or a filter like `info,boggle_server=debug`
+    #[structopt(long = "log-level")]
+    log_level: Option<String>,
+    /// text, or json for one object per line
+    #[structopt(long = "log-format")]
+    log_format: Option<String>,
 }
 
 fn main() {
     let opt = Opt::from_args();
-    let addr = (&*opt.host, opt.port);
+    let config = load_config(&opt).unwrap_or_else(|e| {
+        eprintln!("Invalid configuration: {}", e);
+        process::exit(1);
+    });
+
+    init_logging(&config);
+
+    for deprecation in config.deprecations() {
+        warn!("{}", deprecation);
+    }
+
     let system = System::new("game");
-    let server = Server::new().start();
-
-    HttpServer::new(move || {
-        let server = server.clone();
-        App::new()
-        .service(web::resource("/").route(web::get().to(move |req: HttpRequest, stream: web::Payload| {
-            ws::start(
-                Client::new(server.clone()),
-                &req,
-                stream,
-            )
-            .unwrap()
-        })))
+    let server = build_server(&config).unwrap_or_else(|e| {
+        error!(error = %e, "Failed to start server");
+        process::exit(1);
+    });
+    let server = server.start();
+
+    let dictionaries = config.dictionaries.clone();
+    let admin = actix_web::web::Data::new(Admin {
+        token: config.server.admin_token.clone(),
+        load_dictionaries: Arc::new(move || load_dicts(&dictionaries)),
+    });
+
+    #[cfg(unix)]
+    actix_rt::spawn(reload_on_hangup(admin.clone(), server.clone()));
+
+    let static_dir = config.server.static_dir.clone();
+    let limits = config.limits();
+    let proxies = TrustedProxies(config.server.trusted_proxies.clone());
+    let game = server.clone();
+
+    let http = HttpServer::new(move || {
+        let app = App::new()
+        .data(server.clone())
+        .data(limits.clone())
+        .data(proxies.clone())
+        .app_data(admin.clone())
+        .configure(http::configure)
+        .configure(websocket::configure)
+        .configure(admin::configure);
+
+        // Registered last, as it matches every path
+        match &static_dir {
+            Some(dir) => app.service(Files::new("/", dir).index_file("index.html")),
+            None => app,
+        }
     })
-    .bind(addr).unwrap()
+    .bind((&*config.server.host, config.server.port)).unwrap()
+    // Signals are handled below, so the game is saved before stopping
+    .disable_signals()
     .run();
 
-    println!("Listening on {}:{}", opt.host, opt.port);
+    #[cfg(unix)]
+    {
+        use actix_rt::signal::unix::SignalKind;
+
+        actix_rt::spawn(shutdown_on(SignalKind::terminate(), game.clone(), http.clone()));
+        actix_rt::spawn(shutdown_on(SignalKind::interrupt(), game, http));
+    }
+
+    info!(host = %config.server.host, port = config.server.port, "Listening");
 
     system.run().unwrap();
 }
+
+/// Read the config file, apply the command line options and validate the result.
+fn load_config(opt: &Opt) -> Result<Config, Error> {
+    let mut config = match &opt.config {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+
+    if let Some(host) = &opt.host {
+        config.server.host = host.clone();
+    }
+
+    if let Some(port) = opt.port {
+        config.server.port = port;
+    }
+
+    if let Some(static_dir) = &opt.static_dir {
+        config.server.static_dir = Some(static_dir.clone());
+    }
+
+    if let Some(dict_dir) = &opt.dict_dir {
+        config.dictionaries.dir = Some(dict_dir.clone());
+    }
+
+    config.dictionaries.files.extend(opt.dicts.iter().cloned());
+
+    if let Some(default_dict) = &opt.default_dict {
+        config.game.dictionary = default_dict.clone();
+    }
+
+    if let Some(round) = opt.round {
+        config.game.round = round;
+    }
+
+    if let Some(intermission) = opt.intermission {
+        config.game.intermission = intermission;
+    }
+
+    if let Some(dice) = &opt.dice {
+        config.game.dice = dice.clone();
+    }
+
+    if let Some(max_players) = opt.max_players {
+        config.limits.max_players = Some(max_players);
+    }
+
+    if let Some(bots) = opt.bots {
+        config.game.bots = bots;
+    }
+
+    if let Some(bot_skill) = &opt.bot_skill {
+        config.game.bot_skill = bot_skill.clone();
+    }
+
+    if opt.no_chat_during_play {
+        config.game.chat_during_play = false;
+    }
+
+    if opt.reveal_results {
+        config.game.reveal_results = true;
+    }
+
+    if let Some(replay_dir) = &opt.replay_dir {
+        config.persistence.replay_dir = Some(replay_dir.clone());
+    }
+
+    if let Some(snapshot) = &opt.snapshot {
+        config.persistence.snapshot = Some(snapshot.clone());
+    }
+
+    if let Some(log_level) = &opt.log_level {
+        config.log.level = log_level.clone();
+    }
+
+    if let Some(log_format) = &opt.log_format {
+        config.log.format = log_format.clone();
+    }
+
+    config.validate()?;
+
+    Ok(config)
+}
+
+/// Log to stderr, filtered by `RUST_LOG` if set and `log.level` otherwise.
+fn init_logging(config: &Config) {
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| config.log_filter().unwrap());
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr);
+
+    if config.log_json().unwrap() {
+        subscriber.json().init();
+    } else {
+        subscriber.init();
+    }
+}
+
+fn build_server(config: &Config) -> Result<Server, Error> {
+    let dicts = load_dicts(&config.dictionaries)?;
+    let mut server = Server::new(dicts, config.settings()?)?;
+    let skill = config.bot_skill()?;
+
+    for i in 1..=config.game.bots {
+        server.add_bot(Bot::new(format!("bot{}", i), skill.clone()))?;
+    }
+
+    Ok(server)
+}
+
+fn load_dicts(config: &DictionariesConfig) -> io::Result<Registry> {
+    let start = Instant::now();
+    let mut dicts = Registry::builtin();
+
+    if let Some(dir) = &config.dir {
+        dicts.load_dir(dir)?;
+    }
+
+    for path in &config.files {
+        dicts.load_file(path)?;
+    }
+
+    METRICS.dictionary_load.observe(start.elapsed());
+
+    Ok(dicts)
+}
+
+/// Reload all dictionaries whenever the process receives `SIGHUP`.
+/// The new dictionaries are used starting with the next game.
+#[cfg(unix)]
+async fn reload_on_hangup(admin: actix_web::web::Data<Admin>, server: Addr<Server>) {
+    use actix_rt::signal::unix::{signal, SignalKind};
+
+    let mut hangup = signal(SignalKind::hangup()).expect("failed to listen for SIGHUP");
+
+    while let Some(()) = hangup.recv().await {
+        match admin.reload(&server).await {
+            Ok(()) => info!("Reloaded dictionaries"),
+            Err(e) => error!(error = %e, "Failed to reload dictionaries"),
+        }
+    }
+}
+
+/// Save the game, disconnect all clients and exit once the process receives `kind`.
+#[cfg(unix)]
+async fn shutdown_on(kind: actix_rt::signal::unix::SignalKind, server: Addr<Server>, http: actix_web::dev::Server) {
+    use actix_rt::signal::unix::signal;
+
+    let mut signal = signal(kind).expect("failed to listen for signal");
+
+    if signal.recv().await.is_none() {
+        return;
+    }
+
+    info!("Shutting down");
+
+    if let Err(e) = server.send(Shutdown).await {
+        error!(error = %e, "Failed to save game");
+    }
+
+    http.stop(true).await;
+    System::current().stop();
+}
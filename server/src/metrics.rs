@@ -0,0 +1,271 @@
+//! Counters for monitoring, served in the Prometheus text format at `/metrics`.
+
+use boggle_common::replay::Verdict;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Metrics of this process.
+pub static METRICS: Metrics = Metrics::new();
+
+pub struct Metrics {
+    pub connected_clients: Gauge,
+    /// 1 while a round is being played, 0 during the intermission
+    pub active_games: Gauge,
+    pub rounds_played: Counter,
+    submissions_accepted: Counter,
+    submissions_invalid: Counter,
+    submissions_already_found: Counter,
+    /// Frames that couldn't be decoded into a message
+    pub decode_errors: Counter,
+    /// Messages that couldn't be queued for a client
+    pub send_failures: Counter,
+    /// Messages dropped because a client exceeded a rate limit
+    pub rate_limited: Counter,
+    /// Clients disconnected and banned for exceeding rate limits
+    pub bans: Counter,
+    /// Time to find all words of a board
+    pub solver: Histogram,
+    /// Time to load all dictionaries, at startup and on reload
+    pub dictionary_load: Histogram,
+}
+
+impl Metrics {
+    const fn new() -> Self {
+        Self {
+            connected_clients: Gauge::new(),
+            active_games: Gauge::new(),
+            rounds_played: Counter::new(),
+            submissions_accepted: Counter::new(),
+            submissions_invalid: Counter::new(),
+            submissions_already_found: Counter::new(),
+            decode_errors: Counter::new(),
+            send_failures: Counter::new(),
+            rate_limited: Counter::new(),
+            bans: Counter::new(),
+            solver: Histogram::new(&SOLVER_BUCKETS),
+            dictionary_load: Histogram::new(&DICTIONARY_LOAD_BUCKETS),
+        }
+    }
+
+    pub fn submitted(&self, verdict: Verdict) {
+        match verdict {
+            Verdict::Accepted => self.submissions_accepted.inc(),
+            Verdict::Invalid => self.submissions_invalid.inc(),
+            Verdict::AlreadyFound => self.submissions_already_found.inc(),
+        }
+    }
+
+    /// All metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        header(&mut out, "boggle_connected_clients", "gauge", "Connected websocket clients, including spectators");
+        sample(&mut out, "boggle_connected_clients", "", self.connected_clients.get());
+
+        header(&mut out, "boggle_active_games", "gauge", "Games with a round in progress");
+        sample(&mut out, "boggle_active_games", "", self.active_games.get());
+
+        header(&mut out, "boggle_rounds_played_total", "counter", "Rounds played to their end");
+        sample(&mut out, "boggle_rounds_played_total", "", self.rounds_played.get());
+
+        header(&mut out, "boggle_submissions_total", "counter", "Words submitted by players, by verdict");
+        sample(&mut out, "boggle_submissions_total", r#"{verdict="accepted"}"#, self.submissions_accepted.get());
+        sample(&mut out, "boggle_submissions_total", r#"{verdict="invalid"}"#, self.submissions_invalid.get());
+        sample(&mut out, "boggle_submissions_total", r#"{verdict="already_found"}"#, self.submissions_already_found.get());
+
+        header(&mut out, "boggle_decode_errors_total", "counter", "Frames that couldn't be decoded");
+        sample(&mut out, "boggle_decode_errors_total", "", self.decode_errors.get());
+
+        header(&mut out, "boggle_send_failures_total", "counter", "Messages that couldn't be queued for a client");
+        sample(&mut out, "boggle_send_failures_total", "", self.send_failures.get());
+
+        header(&mut out, "boggle_rate_limited_total", "counter", "Messages dropped because of rate limits");
+        sample(&mut out, "boggle_rate_limited_total", "", self.rate_limited.get());
+
+        header(&mut out, "boggle_bans_total", "counter", "Clients banned for exceeding rate limits");
+        sample(&mut out, "boggle_bans_total", "", self.bans.get());
+
+        header(&mut out, "boggle_solver_seconds", "histogram", "Time to find all words of a board");
+        self.solver.render(&mut out, "boggle_solver_seconds");
+
+        header(&mut out, "boggle_dictionary_load_seconds", "histogram", "Time to load all dictionaries");
+        self.dictionary_load.render(&mut out, "boggle_dictionary_load_seconds");
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    writeln!(out, "# HELP {} {}", name, help).unwrap();
+    writeln!(out, "# TYPE {} {}", name, kind).unwrap();
+}
+
+fn sample(out: &mut String, name: &str, labels: &str, value: u64) {
+    writeln!(out, "{}{} {}", name, labels, value).unwrap();
+}
+
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    const fn new() -> Self {
+        Counter(AtomicU64::new(0))
+    }
+
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+pub struct Gauge(AtomicU64);
+
+impl Gauge {
+    const fn new() -> Self {
+        Gauge(AtomicU64::new(0))
+    }
+
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn dec(&self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn set(&self, value: u64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Number of buckets of every histogram, besides `+Inf`
+const BUCKETS: usize = 10;
+
+/// Upper bounds of the solver's buckets, in seconds
+const SOLVER_BUCKETS: [f64; BUCKETS] = [0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0];
+
+/// Upper bounds of the dictionary load's buckets, in seconds.
+/// Large word lists take seconds to parse.
+const DICTIONARY_LOAD_BUCKETS: [f64; BUCKETS] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 30.0];
+
+/// Durations, counted into buckets with the given upper bounds.
+pub struct Histogram {
+    bounds: &'static [f64; BUCKETS],
+    /// Observations per bucket, not cumulative. The last one is `+Inf`.
+    buckets: [AtomicU64; BUCKETS + 1],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    const fn new(bounds: &'static [f64; BUCKETS]) -> Self {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const ZERO: AtomicU64 = AtomicU64::new(0);
+
+        Self {
+            bounds,
+            buckets: [ZERO; BUCKETS + 1],
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    pub fn observe(&self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        let bucket = self.bounds.iter()
+            .position(|&bound| seconds <= bound)
+            .unwrap_or(BUCKETS);
+
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn render(&self, out: &mut String, name: &str) {
+        let mut cumulative = 0;
+
+        for (bound, bucket) in self.bounds.iter().zip(&self.buckets) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative).unwrap();
+        }
+
+        cumulative += self.buckets[BUCKETS].load(Ordering::Relaxed);
+        writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, cumulative).unwrap();
+        writeln!(out, "{}_sum {}", name, self.sum_micros.load(Ordering::Relaxed) as f64 / 1e6).unwrap();
+        writeln!(out, "{}_count {}", name, self.count.load(Ordering::Relaxed)).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram() {
+        let histogram = Histogram::new(&SOLVER_BUCKETS);
+
+        for &millis in &[1, 3, 40, 2000] {
+            histogram.observe(Duration::from_millis(millis));
+        }
+
+        let mut out = String::new();
+        histogram.render(&mut out, "test_seconds");
+
+        assert_eq!(out, "\
+            test_seconds_bucket{le=\"0.001\"} 1\n\
+            test_seconds_bucket{le=\"0.0025\"} 1\n\
+            test_seconds_bucket{le=\"0.005\"} 2\n\
+            test_seconds_bucket{le=\"0.01\"} 2\n\
+            test_seconds_bucket{le=\"0.025\"} 2\n\
+            test_seconds_bucket{le=\"0.05\"} 3\n\
+            test_seconds_bucket{le=\"0.1\"} 3\n\
+            test_seconds_bucket{le=\"0.25\"} 3\n\
+            test_seconds_bucket{le=\"0.5\"} 3\n\
+            test_seconds_bucket{le=\"1\"} 3\n\
+            test_seconds_bucket{le=\"+Inf\"} 4\n\
+            test_seconds_sum 2.044\n\
+            test_seconds_count 4\n\
+        ");
+    }
+
+    #[test]
+    fn render() {
+        let metrics = Metrics::new();
+
+        metrics.submitted(Verdict::Accepted);
+        metrics.submitted(Verdict::Accepted);
+        metrics.submitted(Verdict::AlreadyFound);
+        metrics.solver.observe(Duration::from_micros(1500));
+        metrics.dictionary_load.observe(Duration::from_secs(3));
+
+        let out = metrics.render();
+        let lines: Vec<&str> = out.lines().collect();
+
+        for line in &[
+            "# TYPE boggle_submissions_total counter",
+            "boggle_submissions_total{verdict=\"accepted\"} 2",
+            "boggle_submissions_total{verdict=\"invalid\"} 0",
+            "boggle_submissions_total{verdict=\"already_found\"} 1",
+            "# TYPE boggle_solver_seconds histogram",
+            "boggle_solver_seconds_bucket{le=\"0.001\"} 0",
+            "boggle_solver_seconds_bucket{le=\"0.0025\"} 1",
+            "boggle_solver_seconds_bucket{le=\"+Inf\"} 1",
+            "boggle_solver_seconds_sum 0.0015",
+            "boggle_solver_seconds_count 1",
+            "# TYPE boggle_dictionary_load_seconds histogram",
+            "boggle_dictionary_load_seconds_bucket{le=\"2.5\"} 0",
+            "boggle_dictionary_load_seconds_bucket{le=\"5\"} 1",
+            "boggle_dictionary_load_seconds_bucket{le=\"30\"} 1",
+            "boggle_dictionary_load_seconds_count 1",
+        ] {
+            assert!(lines.contains(line), "missing '{}' in:\n{}", line, out);
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use boggle_common::replay::Event;
+use chrono::{DateTime, Utc};
+use failure::Error;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Appends the events of a single round to its log file.
+pub struct ReplayLog {
+    path: PathBuf,
+    file: File,
+}
+
+impl ReplayLog {
+    /// Create the log of a round that starts at `time` in `dir`.
+    pub fn create(dir: &Path, time: DateTime<Utc>) -> Result<Self, Error> {
+        fs::create_dir_all(dir)?;
+
+        let path = dir.join(format!("{}.jsonl", time.format("%Y%m%dT%H%M%S%.3fZ")));
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+
+        Ok(Self {
+            path,
+            file,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn log(&mut self, event: &Event) -> Result<(), Error> {
+        // Write whole lines at once, so a crash can't leave half an event behind
+        let mut line = Vec::new();
+        event.write(&mut line)?;
+        self.file.write_all(&line)?;
+
+        Ok(())
+    }
+}
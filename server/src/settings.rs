@@ -0,0 +1,49 @@
+use boggle::Dice;
+use chrono::Duration;
+use std::path::PathBuf;
+
+/// How the server runs its games.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// Id of the dictionary used for games unless players select another one
+    pub dictionary: String,
+    /// How long players have to find words
+    pub round: Duration,
+    /// Pause between the end of a round and the next grid
+    pub intermission: Duration,
+    /// Dice to roll grids with, weighted random letters if `None`
+    pub dice: Option<Dice>,
+    /// Number of players that may join, unlimited if `None`.
+    /// Bots and spectators don't count, players of a restored game that didn't resume yet do.
+    pub max_players: Option<usize>,
+    /// Whether players may chat before the end of a round
+    pub chat_during_play: bool,
+    /// Words not on the grid a player may submit per round
+    pub max_invalid_words: usize,
+    /// Whether spectators get the words of every player when a round ends
+    pub reveal_results: bool,
+    /// Directory that receives a replay log of every round
+    pub replay_dir: Option<PathBuf>,
+    /// File the game in progress is saved to on shutdown and restored from on start
+    pub snapshot: Option<PathBuf>,
+    /// Number of ended rounds kept for the history and leaderboard of the HTTP API
+    pub history: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            dictionary: dict::DEFAULT_ID.into(),
+            round: Duration::minutes(10),
+            intermission: Duration::zero(),
+            dice: None,
+            max_players: None,
+            chat_during_play: true,
+            max_invalid_words: 200,
+            reveal_results: false,
+            replay_dir: None,
+            snapshot: None,
+            history: 100,
+        }
+    }
+}
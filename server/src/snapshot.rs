@@ -0,0 +1,113 @@
+use boggle::Grid;
+use boggle_common::api;
+use chrono::{DateTime, Utc};
+use failure::Error;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// State of the game when the server shut down, restored when it starts again.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Snapshot {
+    pub grid: Grid,
+    pub dictionary: String,
+    pub next_dictionary: String,
+    pub started: DateTime<Utc>,
+    pub deadline: DateTime<Utc>,
+    pub players: Vec<PlayerSnapshot>,
+    /// Words found by bots, by nick. Bots themselves come from the configuration.
+    pub bots: Vec<(String, Vec<String>)>,
+    /// Ended rounds, oldest first
+    pub history: Vec<api::Round>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct PlayerSnapshot {
+    pub nick: String,
+    pub token: String,
+    pub words: Vec<String>,
+}
+
+impl Snapshot {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let file = File::open(path)?;
+        let snapshot = serde_json::from_reader(BufReader::new(file))?;
+
+        Ok(snapshot)
+    }
+
+    /// Write the snapshot to `path`, replacing it only once it is complete.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let partial = path.with_extension("partial");
+        let mut file = BufWriter::new(File::create(&partial)?);
+
+        serde_json::to_writer(&mut file, self)?;
+        file.flush()?;
+        fs::rename(&partial, path)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use boggle_common::client::message::{RoundResults, PlayerResult};
+    use chrono::TimeZone;
+    use super::*;
+
+    fn snapshot() -> Snapshot {
+        let grid: Grid = "abcdefghijklmnop".parse().unwrap();
+
+        Snapshot {
+            grid,
+            dictionary: "english".into(),
+            next_dictionary: "kids".into(),
+            started: Utc.timestamp(600, 0),
+            deadline: Utc.timestamp(1200, 0),
+            players: vec![PlayerSnapshot {
+                nick: "ann".into(),
+                token: "abc".into(),
+                words: vec!["fab".into()],
+            }],
+            bots: vec![("bot1".into(), vec!["jin".into()])],
+            history: vec![api::Round {
+                started: Utc.timestamp(0, 0),
+                ended: Utc.timestamp(600, 0),
+                dictionary: "english".into(),
+                results: RoundResults {
+                    grid,
+                    players: vec![PlayerResult {
+                        nick: "ann".into(),
+                        words: vec!["fab".into()],
+                        score: 1,
+                    }],
+                },
+            }],
+        }
+    }
+
+    #[test]
+    fn save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("game.json");
+
+        snapshot().save(&path).unwrap();
+
+        assert_eq!(Snapshot::load(&path).unwrap(), snapshot());
+        assert!(!path.with_extension("partial").exists());
+    }
+
+    #[test]
+    fn save_replaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("game.json");
+        let mut later = snapshot();
+        later.players.clear();
+
+        snapshot().save(&path).unwrap();
+        later.save(&path).unwrap();
+
+        assert_eq!(Snapshot::load(&path).unwrap(), later);
+    }
+}
@@ -0,0 +1,130 @@
+//! The websocket clients play over, see `common/PROTOCOL.md`.
+
+use ::actix::prelude::*;
+use actix_web::{web, HttpRequest, HttpResponse};
+use actix_web_actors::ws;
+use std::net::{IpAddr, SocketAddr};
+use crate::{Server, Client, IsBanned, Limits};
+
+/// Proxies whose `X-Forwarded-For` headers are believed.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(pub Vec<IpAddr>);
+
+/// Mount the websocket at `/ws`.
+/// Expects the server's address, the `Limits` and the `TrustedProxies` as app data.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/ws", web::get().to(websocket));
+}
+
+async fn websocket(
+    req: HttpRequest,
+    stream: web::Payload,
+    server: web::Data<Addr<Server>>,
+    limits: web::Data<Limits>,
+    proxies: web::Data<TrustedProxies>,
+) -> actix_web::Result<HttpResponse> {
+    // Bans apply to the whole host, so the port is dropped
+    let peer = peer(&req, &proxies.0).map(|ip| ip.to_string());
+
+    if let Some(peer) = &peer {
+        if server.send(IsBanned { peer: peer.clone() }).await.unwrap_or(false) {
+            return Ok(HttpResponse::Forbidden().finish());
+        }
+    }
+
+    let codec = ws::Codec::new().max_size(limits.max_frame_size);
+    let client = Client::new(server.get_ref().clone(), peer, limits.get_ref().clone());
+    let mut response = ws::handshake(&req)?;
+
+    Ok(response.streaming(ws::WebsocketContext::with_codec(client, stream, codec)))
+}
+
+/// Address of the client that sent `req`.
+///
+/// A connection from a trusted proxy belongs to the last address in
+/// `X-Forwarded-For` that isn't a trusted proxy itself. Each proxy appends the
+/// address it got the request from, so anything left of that may be made up.
+fn peer(req: &HttpRequest, trusted: &[IpAddr]) -> Option<IpAddr> {
+    let mut peer = req.peer_addr()?.ip();
+
+    if !trusted.contains(&peer) {
+        return Some(peer);
+    }
+
+    let forwarded: Vec<&str> = req.headers().get_all("x-forwarded-for")
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+
+    for addr in forwarded.into_iter().rev() {
+        let addr = match addr.parse::<IpAddr>().or_else(|_| addr.parse::<SocketAddr>().map(|addr| addr.ip())) {
+            Ok(addr) => addr,
+            Err(_) => break,
+        };
+
+        peer = addr;
+
+        if !trusted.contains(&peer) {
+            break;
+        }
+    }
+
+    Some(peer)
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+    use super::*;
+
+    fn ip(addr: &str) -> IpAddr {
+        addr.parse().unwrap()
+    }
+
+    fn request(peer: &str, forwarded: Option<&str>) -> HttpRequest {
+        let mut req = TestRequest::default().peer_addr(peer.parse().unwrap());
+
+        if let Some(forwarded) = forwarded {
+            req = req.header("x-forwarded-for", forwarded);
+        }
+
+        req.to_http_request()
+    }
+
+    #[test]
+    fn direct_peer_ignores_forwarding_header() {
+        let req = request("203.0.113.7:4000", Some("198.51.100.1"));
+
+        assert_eq!(peer(&req, &[]), Some(ip("203.0.113.7")));
+        assert_eq!(peer(&req, &[ip("10.0.0.1")]), Some(ip("203.0.113.7")));
+    }
+
+    #[test]
+    fn trusted_proxy_forwards_client() {
+        let req = request("10.0.0.1:4000", Some("198.51.100.1"));
+
+        assert_eq!(peer(&req, &[ip("10.0.0.1")]), Some(ip("198.51.100.1")));
+    }
+
+    #[test]
+    fn addresses_left_of_the_proxy_are_ignored() {
+        let req = request("10.0.0.1:4000", Some("192.0.2.66, garbage, 198.51.100.1"));
+
+        assert_eq!(peer(&req, &[ip("10.0.0.1")]), Some(ip("198.51.100.1")));
+    }
+
+    #[test]
+    fn chained_trusted_proxies_are_skipped() {
+        let req = request("10.0.0.1:4000", Some("198.51.100.1:5000, 10.0.0.2"));
+
+        assert_eq!(peer(&req, &[ip("10.0.0.1"), ip("10.0.0.2")]), Some(ip("198.51.100.1")));
+    }
+
+    #[test]
+    fn trusted_proxy_without_header() {
+        let req = request("10.0.0.1:4000", None);
+
+        assert_eq!(peer(&req, &[ip("10.0.0.1")]), Some(ip("10.0.0.1")));
+    }
+}
@@ -0,0 +1,641 @@
+//! Plays against a real server over its websocket, as described in `common/PROTOCOL.md`.
+
+use ::actix::prelude::*;
+use actix_rt::time::timeout;
+use actix_web::{test, App};
+use actix_web_actors::ws;
+use boggle_common::{api, client, server, Codec, MAX_CHAT_LENGTH, PROTOCOL_VERSION};
+use boggle_common::client::message::{ErrorCode, NewGame, PlayerStatus, Session, Welcome};
+use boggle_server::{Server, Settings, Limits, Rate, Shutdown, http, websocket};
+use boggle_server::websocket::TrustedProxies;
+use boggle::{Definitions, Dict};
+use dict::{Entry, Registry};
+use futures_util::{SinkExt, StreamExt};
+use futures_util::sink::Sink;
+use futures_util::stream::Stream;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long to wait for the server before failing
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Start a game and serve its websocket.
+fn start(settings: Settings, limits: Limits) -> (Addr<Server>, test::TestServer) {
+    start_with(Registry::builtin(), settings, limits)
+}
+
+/// Start a game with the dictionaries `dicts` and serve its websocket.
+fn start_with(dicts: Registry, settings: Settings, limits: Limits) -> (Addr<Server>, test::TestServer) {
+    let server = Server::new(dicts, settings).unwrap().start();
+    let addr = server.clone();
+
+    let srv = test::start(move || {
+        App::new()
+            .data(server.clone())
+            .data(limits.clone())
+            .data(TrustedProxies::default())
+            .configure(http::configure)
+            .configure(websocket::configure)
+    });
+
+    (addr, srv)
+}
+
+trait Socket: Sink<ws::Message, Error = ws::ProtocolError> + Stream<Item = Result<ws::Frame, ws::ProtocolError>> + Unpin {}
+
+impl<T> Socket for T
+where
+    T: Sink<ws::Message, Error = ws::ProtocolError> + Stream<Item = Result<ws::Frame, ws::ProtocolError>> + Unpin,
+{}
+
+async fn connect(srv: &mut test::TestServer) -> Connection<impl Socket> {
+    Connection {
+        framed: srv.ws_at("/ws").await.expect("failed to connect"),
+    }
+}
+
+struct Connection<T> {
+    framed: T,
+}
+
+impl<T: Socket> Connection<T> {
+    async fn send(&mut self, msg: server::Message) {
+        let data = msg.to_vec().unwrap();
+        self.framed.send(ws::Message::Binary(data.into())).await.unwrap();
+    }
+
+    async fn send_json(&mut self, msg: server::Message) {
+        let data = msg.to_vec_with(Codec::Json).unwrap();
+        self.framed.send(ws::Message::Text(String::from_utf8(data).unwrap())).await.unwrap();
+    }
+
+    async fn frame(&mut self) -> ws::Frame {
+        loop {
+            let frame = timeout(TIMEOUT, self.framed.next()).await
+                .expect("timed out waiting for the server")
+                .expect("connection ended without a close frame")
+                .unwrap();
+
+            match frame {
+                ws::Frame::Ping(_) | ws::Frame::Pong(_) => {},
+                frame => return frame,
+            }
+        }
+    }
+
+    async fn recv(&mut self) -> client::Message {
+        match self.frame().await {
+            ws::Frame::Binary(data) => client::Message::from_slice(&data).unwrap(),
+            ws::Frame::Text(data) => client::Message::from_slice_with(Codec::Json, &data).unwrap(),
+            frame => panic!("expected a message, got {:?}", frame),
+        }
+    }
+
+    /// Skip messages until `select` returns something, failing on errors it doesn't select.
+    async fn wait_for<R>(&mut self, mut select: impl FnMut(client::Message) -> Option<R>) -> R {
+        loop {
+            let msg = self.recv().await;
+            let error = match &msg {
+                client::Message::Error { code, message } => Some(format!("{:?}: {}", code, message)),
+                _ => None,
+            };
+
+            if let Some(selected) = select(msg) {
+                return selected;
+            }
+
+            if let Some(error) = error {
+                panic!("unexpected error {}", error);
+            }
+        }
+    }
+
+    async fn error(&mut self) -> ErrorCode {
+        self.wait_for(|msg| match msg {
+            client::Message::Error { code, .. } => Some(code),
+            _ => None,
+        }).await
+    }
+
+    /// Skip messages until the server closes the connection.
+    async fn closed(&mut self) -> ws::CloseCode {
+        loop {
+            if let ws::Frame::Close(reason) = self.frame().await {
+                return reason.expect("close frame without reason").code;
+            }
+        }
+    }
+
+    async fn hello(&mut self) -> Welcome {
+        self.send(server::Message::Hello(server::message::Hello {
+            protocol_version: PROTOCOL_VERSION,
+            capabilities: Vec::new(),
+        })).await;
+
+        match self.recv().await {
+            client::Message::Welcome(welcome) => welcome,
+            msg => panic!("expected Welcome, got {:?}", msg),
+        }
+    }
+
+    async fn login(&mut self, nick: &str) -> (NewGame, Session) {
+        self.send(server::Message::Login(server::message::Login { nick: nick.into() })).await;
+
+        let new_game = self.wait_for(|msg| match msg {
+            client::Message::NewGame(new_game) => Some(new_game),
+            _ => None,
+        }).await;
+        let session = self.wait_for(|msg| match msg {
+            client::Message::Session(session) => Some(session),
+            _ => None,
+        }).await;
+
+        (new_game, session)
+    }
+
+    async fn chat(&mut self, text: &str) {
+        self.send(server::Message::Chat(server::message::Chat { text: text.into() })).await;
+    }
+
+    async fn submit(&mut self, word: &str) {
+        self.send(server::Message::SubmitWord(server::message::SubmitWord { word: word.into() })).await;
+    }
+
+    async fn select_dictionary(&mut self, id: &str) {
+        self.send(server::Message::SelectDictionary(server::message::SelectDictionary { id: id.into() })).await;
+    }
+
+    /// Wait for the dictionary of the next game to be announced.
+    async fn selected_dictionary(&mut self) -> String {
+        self.wait_for(|msg| match msg {
+            client::Message::Dictionaries(dictionaries) => Some(dictionaries.selected),
+            _ => None,
+        }).await
+    }
+
+    async fn define(&mut self, word: &str) -> Option<String> {
+        self.send(server::Message::Define(server::message::Define { word: word.into() })).await;
+
+        self.wait_for(|msg| match msg {
+            client::Message::Definition(definition) => Some(definition.definition),
+            _ => None,
+        }).await
+    }
+}
+
+/// Settings of a game that is saved to `dir` on shutdown.
+fn saving_to(dir: &Path) -> Settings {
+    Settings {
+        snapshot: Some(dir.join("game.json")),
+        ..Settings::default()
+    }
+}
+
+/// Log in as `ann`, find a word if there is one and shut the server down.
+async fn save_game(settings: Settings) -> (NewGame, Session, Option<String>) {
+    let (server, mut srv) = start(settings, Limits::default());
+    let mut conn = connect(&mut srv).await;
+
+    conn.hello().await;
+    let (new_game, session) = conn.login("ann").await;
+    let word = new_game.words.unpack().unwrap().words().next().map(String::from);
+
+    if let Some(word) = &word {
+        conn.submit(word).await;
+        conn.wait_for(|msg| match msg {
+            client::Message::PlayerStatus(PlayerStatus::FoundWords { ref nick, count: 1 }) if nick == "ann" => Some(()),
+            _ => None,
+        }).await;
+    }
+
+    server.send(Shutdown).await.unwrap();
+
+    conn.wait_for(|msg| match msg {
+        client::Message::Shutdown(_) => Some(()),
+        _ => None,
+    }).await;
+    assert_eq!(conn.closed().await, ws::CloseCode::Away);
+
+    (new_game, session, word)
+}
+
+/// Wait until a round ended and shows up in the history.
+async fn round_ended(srv: &test::TestServer) {
+    let ended = async {
+        loop {
+            let mut response = srv.get("/api/history").send().await.unwrap();
+            let history: Vec<api::Round> = response.json().await.unwrap();
+
+            if !history.is_empty() {
+                break;
+            }
+
+            actix_rt::time::delay_for(Duration::from_millis(10)).await;
+        }
+    };
+
+    timeout(TIMEOUT, ended).await.expect("round didn't end");
+}
+
+/// Change a field of the saved game.
+fn edit_snapshot(path: &Path, field: &str, value: &str) {
+    let mut snapshot: serde_json::Value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+    snapshot[field] = value.into();
+    fs::write(path, snapshot.to_string()).unwrap();
+}
+
+#[actix_rt::test]
+async fn handshake() {
+    let (_server, mut srv) = start(Settings::default(), Limits::default());
+    let mut conn = connect(&mut srv).await;
+
+    let welcome = conn.hello().await;
+
+    assert_eq!(welcome.protocol_version, PROTOCOL_VERSION);
+    assert!(welcome.capabilities.iter().any(|capability| capability == "resume"));
+}
+
+#[actix_rt::test]
+async fn incompatible_version() {
+    let (_server, mut srv) = start(Settings::default(), Limits::default());
+    let mut conn = connect(&mut srv).await;
+
+    conn.send(server::Message::Hello(server::message::Hello {
+        protocol_version: PROTOCOL_VERSION + 1,
+        capabilities: Vec::new(),
+    })).await;
+
+    match conn.recv().await {
+        client::Message::Incompatible(incompatible) => assert_eq!(incompatible.protocol_version, PROTOCOL_VERSION),
+        msg => panic!("expected Incompatible, got {:?}", msg),
+    }
+
+    assert_eq!(conn.closed().await, ws::CloseCode::Protocol);
+}
+
+#[actix_rt::test]
+async fn login_before_hello() {
+    let (_server, mut srv) = start(Settings::default(), Limits::default());
+    let mut conn = connect(&mut srv).await;
+
+    conn.send(server::Message::Login(server::message::Login { nick: "ann".into() })).await;
+
+    match conn.recv().await {
+        client::Message::Incompatible(_) => {},
+        msg => panic!("expected Incompatible, got {:?}", msg),
+    }
+
+    assert_eq!(conn.closed().await, ws::CloseCode::Protocol);
+}
+
+#[actix_rt::test]
+async fn json_codec() {
+    let (_server, mut srv) = start(Settings::default(), Limits::default());
+    let mut conn = connect(&mut srv).await;
+
+    conn.send_json(server::Message::Hello(server::message::Hello {
+        protocol_version: PROTOCOL_VERSION,
+        capabilities: Vec::new(),
+    })).await;
+
+    match conn.frame().await {
+        ws::Frame::Text(data) => match client::Message::from_slice_with(Codec::Json, &data).unwrap() {
+            client::Message::Welcome(_) => {},
+            msg => panic!("expected Welcome, got {:?}", msg),
+        },
+        frame => panic!("expected a text frame, got {:?}", frame),
+    }
+
+    // Switching codecs after the handshake is not allowed
+    conn.send(server::Message::ListDictionaries).await;
+
+    assert_eq!(conn.error().await, ErrorCode::MalformedMessage);
+    assert_eq!(conn.closed().await, ws::CloseCode::Invalid);
+}
+
+#[actix_rt::test]
+async fn repeated_hello_is_fatal() {
+    let (_server, mut srv) = start(Settings::default(), Limits::default());
+    let mut conn = connect(&mut srv).await;
+
+    conn.hello().await;
+    conn.send(server::Message::Hello(server::message::Hello {
+        protocol_version: PROTOCOL_VERSION,
+        capabilities: Vec::new(),
+    })).await;
+
+    assert_eq!(conn.error().await, ErrorCode::UnexpectedMessage);
+    assert_eq!(conn.closed().await, ws::CloseCode::Protocol);
+}
+
+#[actix_rt::test]
+async fn nick_taken_is_not_fatal() {
+    let (_server, mut srv) = start(Settings::default(), Limits::default());
+    let mut ann = connect(&mut srv).await;
+    let mut other = connect(&mut srv).await;
+
+    ann.hello().await;
+    ann.login("ann").await;
+
+    other.hello().await;
+    other.send(server::Message::Login(server::message::Login { nick: "ann".into() })).await;
+    assert_eq!(other.error().await, ErrorCode::NickTaken);
+
+    let (new_game, _) = other.login("bob").await;
+    assert_eq!(new_game.nick, "bob");
+}
+
+#[actix_rt::test]
+async fn room_full() {
+    let settings = Settings {
+        max_players: Some(1),
+        ..Settings::default()
+    };
+    let (_server, mut srv) = start(settings, Limits::default());
+    let mut ann = connect(&mut srv).await;
+    let mut bob = connect(&mut srv).await;
+
+    ann.hello().await;
+    ann.login("ann").await;
+
+    bob.hello().await;
+    bob.send(server::Message::Login(server::message::Login { nick: "bob".into() })).await;
+
+    assert_eq!(bob.error().await, ErrorCode::RoomFull);
+    assert_eq!(bob.closed().await, ws::CloseCode::Again);
+}
+
+#[actix_rt::test]
+async fn select_dictionary() {
+    let (_server, mut srv) = start(Settings::default(), Limits::default());
+    let mut ann = connect(&mut srv).await;
+    let mut bob = connect(&mut srv).await;
+
+    ann.hello().await;
+    ann.login("ann").await;
+    bob.hello().await;
+    bob.login("bob").await;
+
+    bob.select_dictionary("kids").await;
+    assert_eq!(ann.selected_dictionary().await, "kids");
+
+    ann.select_dictionary("common").await;
+    assert_eq!(bob.selected_dictionary().await, "common");
+}
+
+#[actix_rt::test]
+async fn chat() {
+    let (_server, mut srv) = start(Settings::default(), Limits::default());
+    let mut ann = connect(&mut srv).await;
+    let mut bob = connect(&mut srv).await;
+
+    ann.hello().await;
+    ann.login("ann").await;
+    bob.hello().await;
+    bob.login("bob").await;
+
+    // Control characters are removed before any check, so this is empty…
+    ann.chat("\u{7}\u{1b}").await;
+    ann.chat("  good luck!\u{7} ").await;
+    // …and this isn't too long
+    let long = "a".repeat(MAX_CHAT_LENGTH);
+    ann.chat(&format!("{}{}", long, "\u{7}".repeat(10))).await;
+
+    for conn in &mut [&mut ann, &mut bob] {
+        for text in &["good luck!", &*long] {
+            let chat = conn.wait_for(|msg| match msg {
+                client::Message::Chat(chat) => Some(chat),
+                _ => None,
+            }).await;
+
+            assert_eq!(chat, client::message::Chat { nick: "ann".into(), text: text.to_string() });
+        }
+    }
+}
+
+#[actix_rt::test]
+async fn chat_during_play_disabled() {
+    let settings = Settings {
+        chat_during_play: false,
+        ..Settings::default()
+    };
+    let (_server, mut srv) = start(settings, Limits::default());
+    let mut conn = connect(&mut srv).await;
+
+    conn.hello().await;
+    conn.login("ann").await;
+    conn.chat("hi").await;
+
+    assert_eq!(conn.error().await, ErrorCode::ChatDisabled);
+}
+
+#[actix_rt::test]
+async fn spectator_mistakes_are_not_fatal() {
+    let (_server, mut srv) = start(Settings::default(), Limits::default());
+    let mut conn = connect(&mut srv).await;
+
+    conn.hello().await;
+    conn.send(server::Message::Spectate).await;
+
+    let new_game = conn.wait_for(|msg| match msg {
+        client::Message::NewGame(new_game) => Some(new_game),
+        _ => None,
+    }).await;
+    assert_eq!(new_game.nick, "");
+
+    conn.submit("tea").await;
+    assert_eq!(conn.error().await, ErrorCode::NotAllowed);
+
+    conn.chat("hi").await;
+    assert_eq!(conn.error().await, ErrorCode::NotAllowed);
+
+    // Still connected
+    conn.send(server::Message::ListDictionaries).await;
+    conn.wait_for(|msg| match msg {
+        client::Message::Dictionaries(_) => Some(()),
+        _ => None,
+    }).await;
+}
+
+#[actix_rt::test]
+async fn players_cant_spectate() {
+    let (_server, mut srv) = start(Settings::default(), Limits::default());
+    let mut conn = connect(&mut srv).await;
+
+    conn.hello().await;
+    conn.login("ann").await;
+    conn.send(server::Message::Spectate).await;
+
+    assert_eq!(conn.error().await, ErrorCode::NotAllowed);
+}
+
+#[actix_rt::test]
+async fn rate_limit_then_ban() {
+    let limits = Limits {
+        submissions: Rate { burst: 1, per_second: 0.001 },
+        max_violations: 2,
+        ..Limits::default()
+    };
+    let (_server, mut srv) = start(Settings::default(), limits);
+    let mut conn = connect(&mut srv).await;
+
+    conn.hello().await;
+    conn.login("ann").await;
+
+    conn.submit("tea").await;
+    conn.submit("tea").await;
+    assert_eq!(conn.error().await, ErrorCode::RateLimited);
+
+    conn.submit("tea").await;
+    assert_eq!(conn.error().await, ErrorCode::Banned);
+    assert_eq!(conn.closed().await, ws::CloseCode::Policy);
+
+    assert!(srv.ws_at("/ws").await.is_err(), "banned address could reconnect");
+}
+
+#[actix_rt::test]
+async fn words_are_defined_once_revealed() {
+    let mut dict = Dict::new();
+    dict.extend(vec!["aaa", "bbb"]);
+    let mut definitions = Definitions::new();
+    definitions.insert("aaa", "Three of them");
+    definitions.insert("bbb", "Not on the grid");
+
+    let mut dicts = Registry::new();
+    dicts.insert(Entry {
+        id: "letters".into(),
+        description: "Just letters".into(),
+        dict: Arc::new(dict),
+        definitions: Some(Arc::new(definitions)),
+    });
+
+    // Every grid is all a's
+    let settings = Settings {
+        dictionary: "letters".into(),
+        round: chrono::Duration::seconds(1),
+        intermission: chrono::Duration::hours(1),
+        dice: Some(vec!["aaaaaa"; 16].join(" ").parse().unwrap()),
+        ..Settings::default()
+    };
+
+    let (_server, mut srv) = start_with(dicts, settings, Limits::default());
+    let mut conn = connect(&mut srv).await;
+
+    conn.hello().await;
+    conn.login("ann").await;
+
+    assert_eq!(conn.define("aaa").await, None);
+
+    round_ended(&srv).await;
+
+    assert_eq!(conn.define("aaa").await.as_deref(), Some("Three of them"));
+    assert_eq!(conn.define("bbb").await, None);
+}
+
+#[actix_rt::test]
+async fn restore_then_resume() {
+    let dir = tempfile::tempdir().unwrap();
+    let settings = saving_to(dir.path());
+    let path = settings.snapshot.clone().unwrap();
+
+    let (new_game, session, word) = save_game(settings.clone()).await;
+    assert!(path.exists(), "game wasn't saved");
+
+    let (_server, mut srv) = start(settings, Limits::default());
+    let mut conn = connect(&mut srv).await;
+
+    conn.hello().await;
+    conn.send(server::Message::Resume(server::message::Resume { token: session.token.clone() })).await;
+
+    let resumed = conn.wait_for(|msg| match msg {
+        client::Message::NewGame(new_game) => Some(new_game),
+        _ => None,
+    }).await;
+    let resumed_session = conn.wait_for(|msg| match msg {
+        client::Message::Session(session) => Some(session),
+        _ => None,
+    }).await;
+
+    assert_eq!(resumed.nick, "ann");
+    assert_eq!(resumed.grid, new_game.grid);
+    assert_eq!(resumed.deadline, new_game.deadline);
+    assert_eq!(resumed_session.words, word.into_iter().collect::<Vec<_>>());
+    assert!(!path.exists(), "restored game wasn't removed");
+
+    // Each session can be resumed once
+    let mut other = connect(&mut srv).await;
+    other.hello().await;
+    other.send(server::Message::Resume(server::message::Resume { token: session.token })).await;
+
+    assert_eq!(other.error().await, ErrorCode::UnknownSession);
+}
+
+#[actix_rt::test]
+async fn sessions_end_with_the_round() {
+    let dir = tempfile::tempdir().unwrap();
+    let settings = saving_to(dir.path());
+    let path = settings.snapshot.clone().unwrap();
+
+    let (_, session, _) = save_game(settings.clone()).await;
+    edit_snapshot(&path, "deadline", "2000-01-01T00:00:00Z");
+
+    let (_server, mut srv) = start(settings, Limits::default());
+
+    // The round ends right away
+    round_ended(&srv).await;
+
+    let mut conn = connect(&mut srv).await;
+    conn.hello().await;
+    conn.send(server::Message::Resume(server::message::Resume { token: session.token })).await;
+
+    assert_eq!(conn.error().await, ErrorCode::UnknownSession);
+}
+
+#[actix_rt::test]
+async fn failed_restore_keeps_snapshot() {
+    let dir = tempfile::tempdir().unwrap();
+    let settings = saving_to(dir.path());
+    let path = settings.snapshot.clone().unwrap();
+
+    let (_, session, _) = save_game(settings.clone()).await;
+    edit_snapshot(&path, "dictionary", "klingon");
+
+    let (_server, mut srv) = start(settings, Limits::default());
+    let mut conn = connect(&mut srv).await;
+
+    conn.hello().await;
+    conn.send(server::Message::Resume(server::message::Resume { token: session.token })).await;
+
+    assert_eq!(conn.error().await, ErrorCode::UnknownSession);
+    assert!(path.exists(), "game that couldn't be restored was removed");
+}
+
+#[actix_rt::test]
+async fn players_keep_their_place_until_they_resume() {
+    let dir = tempfile::tempdir().unwrap();
+    let settings = Settings {
+        max_players: Some(1),
+        ..saving_to(dir.path())
+    };
+
+    let (_, session, _) = save_game(settings.clone()).await;
+
+    let (_server, mut srv) = start(settings, Limits::default());
+    let mut bob = connect(&mut srv).await;
+    let mut ann = connect(&mut srv).await;
+
+    bob.hello().await;
+    bob.send(server::Message::Login(server::message::Login { nick: "bob".into() })).await;
+    assert_eq!(bob.error().await, ErrorCode::RoomFull);
+
+    ann.hello().await;
+    ann.send(server::Message::Resume(server::message::Resume { token: session.token })).await;
+
+    let resumed = ann.wait_for(|msg| match msg {
+        client::Message::NewGame(new_game) => Some(new_game),
+        _ => None,
+    }).await;
+    assert_eq!(resumed.nick, "ann");
+}
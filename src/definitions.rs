@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+/// Definitions of words, bundled alongside a dictionary.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Definitions {
+    definitions: HashMap<String, String>,
+}
+
+impl Definitions {
+    pub fn new() -> Self {
+        Self {
+            definitions: HashMap::new(),
+        }
+    }
+
+    /// Add a definition for `word`.
+    /// If the word already has a definition, the existing one is kept.
+    pub fn insert(&mut self, word: impl Into<String>, definition: impl Into<String>) {
+        self.definitions.entry(word.into()).or_insert_with(|| definition.into());
+    }
+
+    pub fn get(&self, word: &str) -> Option<&str> {
+        self.definitions.get(word).map(|s| &**s)
+    }
+
+    pub fn len(&self) -> usize {
+        self.definitions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty()
+    }
+
+    /// Only keep definitions of words for which `f` returns `true`.
+    pub fn retain<F: FnMut(&str) -> bool>(&mut self, mut f: F) {
+        self.definitions.retain(|word, _| f(word));
+    }
+
+    /// Read definitions in the format written by `serialize`:
+    /// one `word<TAB>definition` pair per line.
+    pub fn deserialize<R: BufRead>(r: R) -> io::Result<Self> {
+        let mut definitions = Self::new();
+
+        for line in r.lines() {
+            let line = line?;
+            let mut parts = line.splitn(2, '\t');
+
+            match (parts.next(), parts.next()) {
+                (Some(word), Some(definition)) => definitions.insert(word, definition),
+                _ => return Err(io::Error::new(io::ErrorKind::InvalidData, format!("invalid definition: {}", line))),
+            }
+        }
+
+        Ok(definitions)
+    }
+
+    pub fn serialize<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let mut definitions: Vec<_> = self.definitions.iter().collect();
+        definitions.sort();
+
+        for (word, definition) in definitions {
+            writeln!(w, "{}\t{}", word, definition)?;
+        }
+
+        Ok(())
+    }
+
+    /// Add the definitions from a WordNet data file (e.g. `data.noun`).
+    ///
+    /// Each synset line lists its words followed by the gloss after a `|`.
+    /// Collocations (words containing `_`) are skipped.
+    pub fn extend_from_wordnet<R: BufRead>(&mut self, r: R) -> io::Result<()> {
+        for line in r.lines() {
+            let line = line?;
+
+            // License header lines are indented
+            if line.starts_with(' ') {
+                continue;
+            }
+
+            let mut parts = line.splitn(2, " | ");
+            let (synset, gloss) = match (parts.next(), parts.next()) {
+                (Some(synset), Some(gloss)) => (synset, gloss.trim()),
+                _ => continue,
+            };
+
+            // Fields: offset, lex_filenum, ss_type, w_cnt, [word, lex_id]...
+            let mut fields = synset.split_whitespace().skip(3);
+            let word_count = fields.next()
+                .and_then(|count| usize::from_str_radix(count, 16).ok())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("invalid synset: {}", synset)))?;
+
+            for word in fields.step_by(2).take(word_count) {
+                // Adjectives may carry a syntactic marker like `(a)`
+                let word = word.split('(').next().unwrap_or(word).to_lowercase();
+
+                if word.contains('_') {
+                    continue;
+                }
+
+                self.insert(word, gloss);
+            }
+        }
+
+        Ok(())
+    }
+}
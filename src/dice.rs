@@ -0,0 +1,94 @@
+use std::str::FromStr;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use rand::distributions::Distribution;
+use Grid;
+
+/// A set of six sided dice, one per grid cell.
+///
+/// Grids are sampled by shuffling the dice into the cells and rolling each of them.
+/// Faces showing `Qu` on the physical dice are written as `q`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dice {
+    dice: Vec<[char; Dice::FACES]>,
+}
+
+impl Dice {
+    pub const FACES: usize = 6;
+
+    /// The dice of the original game.
+    pub fn classic() -> Self {
+        [
+            "aaciot", "abilty", "abjmoq", "acdemp", "acelrs", "adenvz", "ahmors", "biforx",
+            "denosw", "dknotu", "eefhiy", "egkluy", "egintv", "ehinps", "elpstu", "gilruw",
+        ].join(" ").parse().unwrap()
+    }
+
+    /// The dice of the revised game, which make rare letters less likely.
+    pub fn revised() -> Self {
+        [
+            "aaeegn", "abbjoo", "achops", "affkps", "aoottw", "cimotu", "deilrx", "delrvy",
+            "distty", "eeghnw", "eeinsu", "ehrtvw", "eiosst", "elrtty", "himnuq", "hlnnrz",
+        ].join(" ").parse().unwrap()
+    }
+
+    pub fn dice(&self) -> &[[char; Dice::FACES]] {
+        &self.dice
+    }
+}
+
+/// Parses dice separated by whitespace or commas, e.g. `"aaciot abilty …"`.
+impl FromStr for Dice {
+    type Err = DiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut dice = Vec::new();
+
+        for die in s.split(|c: char| c.is_whitespace() || c == ',').filter(|die| !die.is_empty()) {
+            if die.chars().count() != Dice::FACES {
+                return Err(DiceError::InvalidFaceCount(die.to_owned()));
+            }
+
+            if let Some(ch) = die.chars().find(|c| !c.is_ascii_lowercase()) {
+                return Err(DiceError::InvalidChar(ch));
+            }
+
+            let mut faces = ['#'; Dice::FACES];
+
+            for (target, src) in faces.iter_mut().zip(die.chars()) {
+                *target = src;
+            }
+
+            dice.push(faces);
+        }
+
+        if dice.len() != Grid::WIDTH * Grid::HEIGHT {
+            return Err(DiceError::InvalidCount);
+        }
+
+        Ok(Dice { dice })
+    }
+}
+
+impl Distribution<Grid> for Dice {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Grid {
+        let mut dice = self.dice.clone();
+        dice.shuffle(rng);
+
+        let grid: String = dice.iter()
+            .map(|faces| faces[rng.gen_range(0, Dice::FACES)])
+            .collect();
+
+        grid.parse().unwrap()
+    }
+}
+
+#[derive(Fail, Debug)]
+pub enum DiceError {
+    #[fail(display = "Invalid count of dice for the grid")]
+    InvalidCount,
+    #[fail(display = "Die '{}' does not have exactly six faces", _0)]
+    InvalidFaceCount(String),
+    #[fail(display = "Invalid die face '{}'", _0)]
+    InvalidChar(char),
+}
@@ -1,9 +1,12 @@
 use sequence_trie::SequenceTrie;
 use std::iter::FromIterator;
 use std::ops;
-use std::io::{self, Read, Write};
-use bitstream_io::{BitReader, BitWriter, BE};
+use std::fs::File;
+use std::io::{self, BufReader, Read, Write};
+use std::path::Path;
+use bitstream_io::{BitWriter, BE};
 use serde;
+use PackedWords;
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Dict {
@@ -55,45 +58,55 @@ impl Dict {
         self.trie.values().map(|s|&**s)
     }
 
-    pub fn deserialize_packed<R: Read>(r: &mut R) -> io::Result<Self> {
-        let mut r = BitReader::<BE>::new(r);
-        let mut dict = Dict::new();
-        let mut state = String::new();
-        let mut skip_emit = false;
+    pub fn contains(&self, word: &str) -> bool {
+        let path: Vec<char> = word.replace("qu", "q").chars().collect();
 
-        loop {
-            match r.read_bit()? {
-                // emit and pop
-                false => {
-                    let n = r.read::<u8>(3)?;
+        self.trie.get(&path).map_or(false, |found| found == word)
+    }
 
-                    // We are done
-                    if n == 0 && skip_emit {
-                        return Ok(dict);
-                    }
+    /// Words that are in `self`, `other` or both.
+    pub fn union(&self, other: &Dict) -> Dict {
+        self.words().chain(other.words()).collect()
+    }
 
-                    if !skip_emit && !state.is_empty() {
-                        dict.add(state.clone());
-                        skip_emit = true;
-                    }
+    /// Words that are in both `self` and `other`.
+    pub fn intersection(&self, other: &Dict) -> Dict {
+        self.words().filter(|word| other.contains(word)).collect()
+    }
 
-                    for _ in 0..n {
-                        state.pop();
-                    }
+    /// Words that are in `self` but not in `other`.
+    pub fn difference(&self, other: &Dict) -> Dict {
+        self.words().filter(|word| !other.contains(word)).collect()
+    }
 
-                }
-                // push
-                true => {
-                    skip_emit = false;
+    /// Extension of packed dictionary files.
+    pub const PACKED_EXTENSION: &'static str = "dict";
 
-                    let ch = r.read::<u8>(5)?;
-                    let ch = b'a' + ch;
-                    state.push(ch as char);
-                }
-            }
+    /// Read a dictionary file, which is packed if its extension is
+    /// `PACKED_EXTENSION` and a plain text word list otherwise.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut file = BufReader::new(File::open(path)?);
+
+        if path.extension().map_or(false, |ext| ext == Self::PACKED_EXTENSION) {
+            Self::deserialize_packed(&mut file)
+        } else {
+            Self::deserialize_text(&mut file)
         }
     }
 
+    /// Read a plain text word list, with words separated by whitespace.
+    pub fn deserialize_text<R: Read>(r: &mut R) -> io::Result<Self> {
+        let mut words = String::new();
+        r.read_to_string(&mut words)?;
+
+        Ok(words.split_whitespace().collect())
+    }
+
+    pub fn deserialize_packed<R: Read>(r: &mut R) -> io::Result<Self> {
+        PackedWords::new(r).collect()
+    }
+
     pub fn serialize_packed<W: Write>(&self, w: &mut W) -> io::Result<()> {
         let mut w = BitWriter::<BE>::new(w);
         let mut words: Vec<&str> = self.words().collect();
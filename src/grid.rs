@@ -1,7 +1,6 @@
 use std::collections::HashSet;
 use std::str::FromStr;
-use sequence_trie::SequenceTrie;
-use Dict;
+use {Dict, Solver};
 use rand::Rng;
 use rand::distributions::{Distribution, Standard, WeightedIndex};
 
@@ -23,60 +22,65 @@ impl Grid {
         self.chars.get(pos).cloned()
     }
 
-    fn cells<'a>(&'a self) -> impl Iterator<Item = (usize, usize, char)> + 'a {
-        self.chars.iter().cloned().enumerate().map(|(i, ch)| (i % Self::WIDTH, i / Self::WIDTH, ch))
+    pub fn words(&self, dict: &Dict) -> HashSet<String> {
+        Self::solver().solve(&self.chars, dict)
+            .into_iter()
+            .map(String::from)
+            .collect()
     }
 
-    fn neighbours<'a>(&'a self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize, char)> + 'a {
-        const DIRS: &[(isize, isize)] = &[
-            (-1, -1), ( 0, -1), ( 1, -1),
-            (-1,  0),           ( 1,  0),
-            (-1,  1), ( 0,  1), ( 1,  1),
-        ];
+    /// Find the cells that spell `word`, as `(x, y)` positions.
+    pub fn path(&self, word: &str) -> Option<Vec<(usize, usize)>> {
+        let word: Vec<char> = word.replace("qu", "q").chars().collect();
 
-        DIRS.iter().flat_map(move |&(dx, dy)| {
-            let x = x.wrapping_add(dx as usize);
-            let y = y.wrapping_add(dy as usize);
+        fn rec(grid: &Grid, word: &[char], path: &mut Vec<(usize, usize)>) -> bool {
+            let (x, y) = match (word.first(), path.last()) {
+                (None, _) => return true,
+                (Some(_), Some(&pos)) => pos,
+                (Some(_), None) => return false,
+            };
 
-            self.get(x, y).map(|c| (x, y, c))
-        })
-    }
+            for ny in y.saturating_sub(1)..(y + 2).min(Grid::HEIGHT) {
+                for nx in x.saturating_sub(1)..(x + 2).min(Grid::WIDTH) {
+                    if path.contains(&(nx, ny)) || grid.get(nx, ny) != Some(word[0]) {
+                        continue;
+                    }
 
-    pub fn words(&self, dict: &Dict) -> HashSet<String> {
-        let mut words = HashSet::new();
-        let mut visited = Vec::new();
+                    path.push((nx, ny));
 
-        fn rec(field: &Grid, visited: &mut Vec<(usize, usize)>, words: &mut HashSet<String>, node: &SequenceTrie<char, String>, x: usize, y: usize) {
-            if let Some(word) = node.value() {
-                words.insert(word.clone());
-            }
+                    if rec(grid, &word[1..], path) {
+                        return true;
+                    }
 
-            if node.is_leaf() {
-                return;
+                    path.pop();
+                }
             }
 
-            for (x, y, ch) in field.neighbours(x, y) {
-                if visited.contains(&(x, y)) {
+            false
+        }
+
+        let first = *word.first()?;
+
+        for y in 0..Self::HEIGHT {
+            for x in 0..Self::WIDTH {
+                if self.get(x, y) != Some(first) {
                     continue;
                 }
 
-                if let Some(node) = node.get_node(&[ch]) {
-                    visited.push((x, y));
-                    rec(field, visited, words, node, x, y);
-                    visited.pop();
+                let mut path = vec![(x, y)];
+
+                if rec(self, &word[1..], &mut path) {
+                    return Some(path);
                 }
             }
         }
 
-        for (x, y, ch) in self.cells() {
-            if let Some(node) = dict.get_node(&[ch]) {
-                visited.push((x, y));
-                rec(self, &mut visited, &mut words, node, x, y);
-                visited.pop();
-            }
-        }
+        None
+    }
 
-        words
+    /// Solver for boards of the size of a grid.
+    pub fn solver() -> Solver {
+        Solver::new(Self::WIDTH, Self::HEIGHT)
     }
 }
 
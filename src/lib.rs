@@ -3,11 +3,30 @@ extern crate failure;
 extern crate rand;
 extern crate bitstream_io;
 extern crate serde;
+#[cfg(feature = "parallel")] extern crate rayon;
 #[macro_use] extern crate failure_derive;
 #[macro_use] extern crate serde_derive;
 
 mod dict;
 pub use self::dict::Dict;
 
+mod packed;
+pub use self::packed::{PackedDict, PackedWords};
+
+mod definitions;
+pub use self::definitions::Definitions;
+
 mod grid;
 pub use self::grid::Grid;
+
+mod solver;
+pub use self::solver::Solver;
+
+mod dice;
+pub use self::dice::{Dice, DiceError};
+
+mod score;
+pub use self::score::points;
+
+mod practice;
+pub use self::practice::{PracticeSession, SubmitError};
@@ -0,0 +1,247 @@
+use std::io::{self, Read};
+use serde::{de, ser, Serialize, Deserialize};
+use Dict;
+
+/// A dictionary in its packed form.
+///
+/// Unlike `Dict` this does not build a trie, which makes it cheap to
+/// create and send around when only membership checks are needed.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PackedDict {
+    data: Vec<u8>,
+}
+
+impl PackedDict {
+    /// Wrap data that was produced by `Dict::serialize_packed`.
+    pub fn from_bytes(data: Vec<u8>) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Decode the words one at a time, in alphabetical order.
+    pub fn words(&self) -> PackedWords<&[u8]> {
+        PackedWords::new(self.data.as_slice())
+    }
+
+    /// Check whether `word` is in the dictionary without building a trie.
+    ///
+    /// This decodes the words in order until `word` is passed, so it takes
+    /// linear time; `unpack` once for many lookups.
+    pub fn contains(&self, word: &str) -> io::Result<bool> {
+        for found in self.words() {
+            let found = found?;
+
+            if found == word {
+                return Ok(true);
+            }
+
+            // Words are sorted, so `word` can't come up anymore
+            if *found > *word {
+                return Ok(false);
+            }
+        }
+
+        Ok(false)
+    }
+
+    pub fn unpack(&self) -> io::Result<Dict> {
+        Dict::deserialize_packed(&mut self.as_bytes())
+    }
+}
+
+impl<'a> From<&'a Dict> for PackedDict {
+    fn from(dict: &'a Dict) -> Self {
+        let mut data = Vec::new();
+        dict.serialize_packed(&mut data).expect("writing to a Vec can't fail");
+        Self::from_bytes(data)
+    }
+}
+
+/// Human readable formats such as JSON get a list of words,
+/// compact ones the packed bytes.
+impl<'de> Deserialize<'de> for PackedDict {
+    fn deserialize<D>(de: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>
+    {
+        if de.is_human_readable() {
+            let mut dict = Dict::new();
+            dict.extend(Vec::<String>::deserialize(de)?);
+            return Ok(PackedDict::from(&dict));
+        }
+
+        let data = Vec::<u8>::deserialize(de)?;
+        Ok(PackedDict::from_bytes(data))
+    }
+}
+
+impl Serialize for PackedDict {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer
+    {
+        if ser.is_human_readable() {
+            let words = self.words()
+                .collect::<io::Result<Vec<String>>>()
+                .map_err(ser::Error::custom)?;
+            return words.serialize(ser);
+        }
+
+        self.data.serialize(ser)
+    }
+}
+
+/// Iterator that decodes the words of a packed dictionary one at a time.
+///
+/// See `Dict::serialize_packed` for the format.
+pub struct PackedWords<R> {
+    bits: Bits<R>,
+    state: String,
+    skip_emit: bool,
+    done: bool,
+}
+
+impl<R: Read> PackedWords<R> {
+    pub fn new(r: R) -> Self {
+        Self {
+            bits: Bits::new(r),
+            state: String::new(),
+            skip_emit: false,
+            done: false,
+        }
+    }
+
+    fn next_word(&mut self) -> io::Result<Option<String>> {
+        loop {
+            let instruction = match self.bits.try_read_bit()? {
+                Some(instruction) => instruction,
+                // Ending between instructions is fine, unless a pushed word wasn't emitted yet
+                None if self.skip_emit || self.state.is_empty() => return Ok(None),
+                None => return Err(io::ErrorKind::UnexpectedEof.into()),
+            };
+
+            match instruction {
+                // emit and pop
+                false => {
+                    let n = self.bits.read(3)?;
+
+                    // We are done, either after the last word or without any word
+                    if n == 0 && (self.skip_emit || self.state.is_empty()) {
+                        return Ok(None);
+                    }
+
+                    let word = if !self.skip_emit && !self.state.is_empty() {
+                        self.skip_emit = true;
+                        Some(self.state.clone())
+                    } else {
+                        None
+                    };
+
+                    for _ in 0..n {
+                        self.state.pop();
+                    }
+
+                    if word.is_some() {
+                        return Ok(word);
+                    }
+                }
+                // push
+                true => {
+                    self.skip_emit = false;
+
+                    let ch = self.bits.read(5)?;
+                    let ch = b'a' + ch;
+                    self.state.push(ch as char);
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for PackedWords<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.next_word() {
+            Ok(Some(word)) => Some(Ok(word)),
+            Ok(None) => {
+                self.done = true;
+                None
+            },
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            },
+        }
+    }
+}
+
+/// Big endian bit reader that owns its source.
+struct Bits<R> {
+    r: R,
+    byte: u8,
+    remaining: u8,
+}
+
+impl<R: Read> Bits<R> {
+    fn new(r: R) -> Self {
+        Self {
+            r,
+            byte: 0,
+            remaining: 0,
+        }
+    }
+
+    /// Like `read_bit`, but `None` if the source ends at a byte boundary.
+    fn try_read_bit(&mut self) -> io::Result<Option<bool>> {
+        if self.remaining == 0 {
+            let mut byte = [0];
+
+            loop {
+                match self.r.read(&mut byte) {
+                    Ok(0) => return Ok(None),
+                    Ok(_) => break,
+                    Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {},
+                    Err(e) => return Err(e),
+                }
+            }
+
+            self.byte = byte[0];
+            self.remaining = 8;
+        }
+
+        self.read_bit().map(Some)
+    }
+
+    fn read_bit(&mut self) -> io::Result<bool> {
+        if self.remaining == 0 {
+            let mut byte = [0];
+            self.r.read_exact(&mut byte)?;
+            self.byte = byte[0];
+            self.remaining = 8;
+        }
+
+        self.remaining -= 1;
+
+        Ok((self.byte >> self.remaining) & 1 == 1)
+    }
+
+    fn read(&mut self, bits: u8) -> io::Result<u8> {
+        let mut value = 0;
+
+        for _ in 0..bits {
+            value = (value << 1) | self.read_bit()? as u8;
+        }
+
+        Ok(value)
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+use rand::{Rng, thread_rng};
+use {Dict, Grid, points};
+
+/// A single player game that runs locally, without a server.
+#[derive(Debug, Clone)]
+pub struct PracticeSession {
+    grid: Grid,
+    words: HashSet<String>,
+    /// Sorted alphabetically
+    found_words: Vec<String>,
+    started: Instant,
+    duration: Duration,
+}
+
+impl PracticeSession {
+    /// Start a session on `grid`, accepting words from `dict`.
+    pub fn new(grid: Grid, dict: &Dict, duration: Duration) -> Self {
+        Self {
+            grid,
+            words: grid.words(dict),
+            found_words: Vec::new(),
+            started: Instant::now(),
+            duration,
+        }
+    }
+
+    /// Start a session on a random grid.
+    pub fn random(dict: &Dict, duration: Duration) -> Self {
+        Self::new(thread_rng().gen(), dict, duration)
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// All words that can be found on the grid.
+    pub fn words(&self) -> &HashSet<String> {
+        &self.words
+    }
+
+    pub fn found_words(&self) -> &[String] {
+        &self.found_words
+    }
+
+    /// Words that have not been found, sorted alphabetically.
+    pub fn missed_words(&self) -> Vec<&str> {
+        let mut missed: Vec<&str> = self.words.iter()
+            .filter(|word| self.found_words.binary_search(word).is_err())
+            .map(|word| &**word)
+            .collect();
+
+        missed.sort();
+        missed
+    }
+
+    pub fn time_left(&self) -> Duration {
+        self.duration.checked_sub(self.started.elapsed()).unwrap_or_default()
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.time_left() == Duration::from_secs(0)
+    }
+
+    /// Total points of all found words.
+    pub fn score(&self) -> u32 {
+        self.found_words.iter().map(|word| points(word)).sum()
+    }
+
+    /// Submit a word, returning the points it is worth.
+    pub fn submit(&mut self, word: &str) -> Result<u32, SubmitError> {
+        let word = word.trim().to_lowercase();
+
+        if self.is_over() {
+            return Err(SubmitError::TimeUp);
+        }
+
+        if !self.words.contains(&word) {
+            return Err(SubmitError::NotOnGrid(word));
+        }
+
+        match self.found_words.binary_search(&word) {
+            Ok(_) => Err(SubmitError::AlreadyFound(word)),
+            Err(index) => {
+                let worth = points(&word);
+                self.found_words.insert(index, word);
+                Ok(worth)
+            },
+        }
+    }
+}
+
+#[derive(Fail, Debug, PartialEq, Eq)]
+pub enum SubmitError {
+    #[fail(display = "Time is up")]
+    TimeUp,
+    #[fail(display = "'{}' is not a word on the grid", _0)]
+    NotOnGrid(String),
+    #[fail(display = "'{}' was found already", _0)]
+    AlreadyFound(String),
+}
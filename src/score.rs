@@ -0,0 +1,13 @@
+/// Points a word is worth, following the official scoring rules.
+///
+/// Letters of a `qu` count separately, so "quiet" is worth as much as any other five letter word.
+pub fn points(word: &str) -> u32 {
+    match word.chars().count() {
+        0..=2 => 0,
+        3 | 4 => 1,
+        5 => 2,
+        6 => 3,
+        7 => 5,
+        _ => 11,
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashSet;
+use sequence_trie::SequenceTrie;
+use Dict;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Finds all words on boards of a fixed size.
+///
+/// Adjacency is precomputed once per board size and visited cells
+/// are tracked in a bitmask, which limits boards to 64 cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solver {
+    width: usize,
+    height: usize,
+    /// Bitmask of the neighbours of each cell
+    neighbours: Vec<u64>,
+}
+
+impl Solver {
+    pub const MAX_CELLS: usize = 64;
+
+    pub fn new(width: usize, height: usize) -> Self {
+        assert!(width * height <= Self::MAX_CELLS, "board must not have more than {} cells", Self::MAX_CELLS);
+
+        let mut neighbours = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let mut mask = 0;
+
+                for ny in y.saturating_sub(1)..(y + 2).min(height) {
+                    for nx in x.saturating_sub(1)..(x + 2).min(width) {
+                        if (nx, ny) != (x, y) {
+                            mask |= 1 << (ny * width + nx);
+                        }
+                    }
+                }
+
+                neighbours.push(mask);
+            }
+        }
+
+        Self {
+            width,
+            height,
+            neighbours,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Find all words of `dict` on the board.
+    /// `cells` are the characters of the board in row-major order.
+    pub fn solve<'d>(&self, cells: &[char], dict: &'d Dict) -> HashSet<&'d str> {
+        assert_eq!(cells.len(), self.neighbours.len(), "board size does not match solver");
+
+        let mut words = HashSet::new();
+
+        for start in 0..cells.len() {
+            self.search(cells, start, dict, &mut words);
+        }
+
+        words
+    }
+
+    /// Like `solve`, but searches from all starting cells in parallel.
+    #[cfg(feature = "parallel")]
+    pub fn solve_parallel<'d>(&self, cells: &[char], dict: &'d Dict) -> HashSet<&'d str> {
+        assert_eq!(cells.len(), self.neighbours.len(), "board size does not match solver");
+
+        (0..cells.len())
+            .into_par_iter()
+            .map(|start| {
+                let mut words = HashSet::new();
+                self.search(cells, start, dict, &mut words);
+                words
+            })
+            .reduce(HashSet::new, |mut words, other| {
+                words.extend(other);
+                words
+            })
+    }
+
+    /// Depth first search for all words starting at cell `start`.
+    fn search<'d>(&self, cells: &[char], start: usize, dict: &'d SequenceTrie<char, String>, words: &mut HashSet<&'d str>) {
+        let node = match dict.get_node(&[cells[start]]) {
+            Some(node) => node,
+            None => return,
+        };
+
+        if let Some(word) = node.value() {
+            words.insert(word);
+        }
+
+        let mut visited: u64 = 1 << start;
+        // Each frame holds a trie node, its cell and the neighbours that are left to try
+        let mut stack = Vec::with_capacity(cells.len());
+        stack.push((node, start, self.neighbours[start] & !visited));
+
+        while let Some(frame) = stack.last_mut() {
+            let (node, cell, candidates) = *frame;
+
+            if candidates == 0 {
+                visited &= !(1 << cell);
+                stack.pop();
+                continue;
+            }
+
+            let next = candidates.trailing_zeros() as usize;
+            frame.2 = candidates & (candidates - 1);
+
+            let child = match node.get_node(&[cells[next]]) {
+                Some(child) => child,
+                None => continue,
+            };
+
+            if let Some(word) = child.value() {
+                words.insert(word);
+            }
+
+            if !child.is_leaf() {
+                visited |= 1 << next;
+                stack.push((child, next, self.neighbours[next] & !visited));
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! The solver `Grid::words` used before `Solver`, kept as a reference.
+
+use boggle::Dict;
+use sequence_trie::SequenceTrie;
+use std::collections::HashSet;
+
+/// Find all words of `dict` on a board like `Solver::solve`, but recursively.
+pub fn naive(width: usize, height: usize, cells: &[char], dict: &Dict) -> HashSet<String> {
+    const DIRS: &[(isize, isize)] = &[
+        (-1, -1), ( 0, -1), ( 1, -1),
+        (-1,  0),           ( 1,  0),
+        (-1,  1), ( 0,  1), ( 1,  1),
+    ];
+
+    struct Board<'a> {
+        width: usize,
+        height: usize,
+        cells: &'a [char],
+    }
+
+    impl<'a> Board<'a> {
+        fn neighbours(&'a self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize, char)> + 'a {
+            DIRS.iter().flat_map(move |&(dx, dy)| {
+                let x = x.wrapping_add(dx as usize);
+                let y = y.wrapping_add(dy as usize);
+
+                if x >= self.width || y >= self.height {
+                    return None;
+                }
+
+                Some((x, y, self.cells[y * self.width + x]))
+            })
+        }
+    }
+
+    fn rec(board: &Board, visited: &mut Vec<(usize, usize)>, words: &mut HashSet<String>, node: &SequenceTrie<char, String>, x: usize, y: usize) {
+        if let Some(word) = node.value() {
+            words.insert(word.clone());
+        }
+
+        if node.is_leaf() {
+            return;
+        }
+
+        for (x, y, ch) in board.neighbours(x, y) {
+            if visited.contains(&(x, y)) {
+                continue;
+            }
+
+            if let Some(node) = node.get_node(&[ch]) {
+                visited.push((x, y));
+                rec(board, visited, words, node, x, y);
+                visited.pop();
+            }
+        }
+    }
+
+    let board = Board { width, height, cells };
+    let mut words = HashSet::new();
+    let mut visited = Vec::new();
+
+    for (i, &ch) in cells.iter().enumerate() {
+        let (x, y) = (i % width, i / width);
+
+        if let Some(node) = dict.get_node(&[ch]) {
+            visited.push((x, y));
+            rec(&board, &mut visited, &mut words, node, x, y);
+            visited.pop();
+        }
+    }
+
+    words
+}
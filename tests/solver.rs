@@ -0,0 +1,81 @@
+extern crate boggle;
+extern crate rand;
+extern crate sequence_trie;
+
+mod naive;
+
+use boggle::{Dict, Grid, Solver};
+use naive::naive;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use std::collections::HashSet;
+
+const SIZES: &[usize] = &[3, 4, 5, 6];
+const SEEDS: u64 = 50;
+
+/// Letters weighted roughly like the dice of the game, with `q` as often as `e` so `qu` words are found
+const LETTERS: &[u8] = b"eeeeeeeeettttttaaaaaarrrrrriiiiiinnnnnoooooossssdddcchhllffmmppuuggywbjkqqqqqqqqqvxz";
+
+fn dict() -> Dict {
+    include_str!("../dict/dict").split_whitespace().collect()
+}
+
+fn board(size: usize, seed: u64) -> Vec<char> {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    (0..size * size)
+        .map(|_| LETTERS[rng.gen_range(0, LETTERS.len())] as char)
+        .collect()
+}
+
+fn owned(words: HashSet<&str>) -> HashSet<String> {
+    words.into_iter().map(String::from).collect()
+}
+
+#[test]
+fn solve_matches_naive() {
+    let dict = dict();
+    let mut qu_words = 0;
+
+    for &size in SIZES {
+        let solver = Solver::new(size, size);
+
+        for seed in 0..SEEDS {
+            let cells = board(size, seed);
+            let expected = naive(size, size, &cells, &dict);
+            let words = owned(solver.solve(&cells, &dict));
+
+            assert_eq!(words, expected, "size {}, seed {}", size, seed);
+
+            #[cfg(feature = "parallel")]
+            assert_eq!(owned(solver.solve_parallel(&cells, &dict)), expected, "size {}, seed {}", size, seed);
+
+            qu_words += words.iter().filter(|word| word.contains("qu")).count();
+        }
+    }
+
+    assert!(qu_words > 0, "no board had a word with 'qu'");
+}
+
+#[test]
+fn grid_words_match_naive() {
+    let dict = dict();
+
+    for seed in 0..SEEDS {
+        let cells = board(Grid::WIDTH, seed);
+        let grid: Grid = cells.iter().collect::<String>().parse().unwrap();
+
+        assert_eq!(grid.words(&dict), naive(Grid::WIDTH, Grid::HEIGHT, &cells, &dict), "seed {}", seed);
+    }
+}
+
+#[test]
+fn q_is_qu() {
+    let dict = dict();
+    let cells: Vec<char> = "qiet".chars().chain("xxxx".chars()).collect();
+    let words = owned(Solver::new(4, 2).solve(&cells, &dict));
+
+    assert!(words.contains("quiet"));
+    assert!(!words.iter().any(|word| word.contains('q') && !word.contains("qu")));
+    assert_eq!(words, naive(4, 2, &cells, &dict));
+}
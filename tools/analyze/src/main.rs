@@ -0,0 +1,191 @@
+extern crate boggle;
+extern crate dict;
+extern crate rand;
+extern crate rayon;
+extern crate structopt;
+extern crate serde;
+#[macro_use] extern crate serde_derive;
+extern crate serde_json;
+
+use boggle::{Dict, Dice, Grid};
+use rand::{Rng, SeedableRng, thread_rng};
+use rand::distributions::Standard;
+use rand::rngs::StdRng;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Generate and solve many boards and report statistics about them.
+#[derive(StructOpt, Debug)]
+struct Opt {
+    /// Number of boards to generate
+    #[structopt(short = "n", long = "boards", default_value = "10000")]
+    boards: u64,
+    /// Seed for reproducible runs, random if omitted
+    #[structopt(long = "seed")]
+    seed: Option<u64>,
+    /// Letter source: `standard` (weighted letters), `classic` or `revised` dice,
+    /// or sixteen custom dice like `aaciot,abilty,…`
+    #[structopt(long = "dice", default_value = "standard")]
+    dice: Source,
+    /// Dictionary to solve with, the built in one if omitted
+    #[structopt(long = "dict", parse(from_os_str))]
+    dict: Option<PathBuf>,
+    /// Output format: `csv` or `json`
+    #[structopt(long = "format", default_value = "csv")]
+    format: Format,
+}
+
+#[derive(Debug)]
+enum Source {
+    Standard,
+    Dice(Dice),
+}
+
+impl std::str::FromStr for Source {
+    type Err = boggle::DiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "standard" => Ok(Source::Standard),
+            "classic" => Ok(Source::Dice(Dice::classic())),
+            "revised" => Ok(Source::Dice(Dice::revised())),
+            dice => dice.parse().map(Source::Dice),
+        }
+    }
+}
+
+impl Source {
+    fn sample<R: Rng>(&self, rng: &mut R) -> Grid {
+        match *self {
+            Source::Standard => rng.sample(Standard),
+            Source::Dice(ref dice) => rng.sample(dice),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Format {
+    Csv,
+    Json,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format '{}'", s)),
+        }
+    }
+}
+
+/// Distributions over all boards, as value → number of boards
+#[derive(Serialize, Default, Debug)]
+struct Stats {
+    boards: u64,
+    word_counts: BTreeMap<usize, u64>,
+    max_points: BTreeMap<u32, u64>,
+    longest_words: BTreeMap<usize, u64>,
+    /// How often each letter appeared on any board
+    letters: BTreeMap<char, u64>,
+}
+
+impl Stats {
+    fn add_board(mut self, grid: &Grid, dict: &Dict) -> Self {
+        let words = grid.words(dict);
+
+        self.boards += 1;
+        *self.word_counts.entry(words.len()).or_insert(0) += 1;
+        *self.max_points.entry(words.iter().map(|word| boggle::points(word)).sum()).or_insert(0) += 1;
+        *self.longest_words.entry(words.iter().map(|word| word.len()).max().unwrap_or(0)).or_insert(0) += 1;
+
+        for y in 0..Grid::HEIGHT {
+            for x in 0..Grid::WIDTH {
+                if let Some(ch) = grid.get(x, y) {
+                    *self.letters.entry(ch).or_insert(0) += 1;
+                }
+            }
+        }
+
+        self
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        fn merge_counts<K: Ord>(a: &mut BTreeMap<K, u64>, b: BTreeMap<K, u64>) {
+            for (key, count) in b {
+                *a.entry(key).or_insert(0) += count;
+            }
+        }
+
+        self.boards += other.boards;
+        merge_counts(&mut self.word_counts, other.word_counts);
+        merge_counts(&mut self.max_points, other.max_points);
+        merge_counts(&mut self.longest_words, other.longest_words);
+        merge_counts(&mut self.letters, other.letters);
+
+        self
+    }
+
+    /// Write the statistics as `metric,value,count` rows.
+    fn write_csv<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "metric,value,count")?;
+        writeln!(w, "boards,,{}", self.boards)?;
+
+        for (value, count) in &self.word_counts {
+            writeln!(w, "word_count,{},{}", value, count)?;
+        }
+
+        for (value, count) in &self.max_points {
+            writeln!(w, "max_points,{},{}", value, count)?;
+        }
+
+        for (value, count) in &self.longest_words {
+            writeln!(w, "longest_word,{},{}", value, count)?;
+        }
+
+        for (value, count) in &self.letters {
+            writeln!(w, "letter,{},{}", value, count)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    let seed = opt.seed.unwrap_or_else(|| thread_rng().gen());
+
+    let custom_dict;
+    let dict: &Dict = match opt.dict {
+        Some(ref path) => {
+            custom_dict = Dict::open(path).expect(&path.display().to_string());
+            &custom_dict
+        },
+        None => &*dict::DICT,
+    };
+
+    // Every board gets its own seed so results don't depend on scheduling
+    let stats = (0..opt.boards)
+        .into_par_iter()
+        .map(|i| opt.dice.sample(&mut StdRng::seed_from_u64(seed.wrapping_add(i))))
+        .fold(Stats::default, |stats, grid| stats.add_board(&grid, dict))
+        .reduce(Stats::default, Stats::merge);
+
+    eprintln!("Analyzed {} boards with seed {}", stats.boards, seed);
+
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    match opt.format {
+        Format::Csv => stats.write_csv(&mut stdout).expect("failed to write statistics"),
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut stdout, &stats).expect("failed to write statistics");
+            writeln!(stdout).expect("failed to write statistics");
+        },
+    }
+}
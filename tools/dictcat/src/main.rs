@@ -1,18 +1,19 @@
 extern crate boggle;
 
-use boggle::Dict;
+use boggle::PackedWords;
 use std::fs::File;
 use std::env::args;
+use std::io::{self, BufReader, BufWriter, Write};
 
 fn main() {
     let src_path = args().nth(1).expect("input dictionary missing");
-    let mut file = File::open(&src_path).expect(&src_path);
+    let file = File::open(&src_path).expect(&src_path);
+    let stdout = io::stdout();
+    let mut stdout = BufWriter::new(stdout.lock());
 
-    let dict = Dict::deserialize_packed(&mut file).expect("failed to read dictionary");
-    let mut words: Vec<&str> = dict.words().collect();
-    words.sort();
-
-    for word in words {
-        println!("{}", word);
+    // Packed dictionaries are sorted already, so words can be printed as they are decoded
+    for word in PackedWords::new(BufReader::new(file)) {
+        let word = word.expect("failed to read dictionary");
+        writeln!(stdout, "{}", word).expect("failed to write word");
     }
 }
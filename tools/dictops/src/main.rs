@@ -0,0 +1,114 @@
+extern crate boggle;
+extern crate structopt;
+
+use boggle::Dict;
+use std::fs::File;
+use std::io::{self, Write, BufWriter};
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+
+/// Compare and combine dictionaries.
+///
+/// Inputs may be packed dictionaries (`.dict`) or plain text word lists.
+#[derive(StructOpt, Debug)]
+enum Opt {
+    /// Show words removed (`-`) and added (`+`) when going from `old` to `new`
+    #[structopt(name = "diff")]
+    Diff {
+        #[structopt(parse(from_os_str))]
+        old: PathBuf,
+        #[structopt(parse(from_os_str))]
+        new: PathBuf,
+    },
+    /// Words that are in any of the dictionaries
+    #[structopt(name = "merge")]
+    Merge(Combine),
+    /// Words that are in all of the dictionaries
+    #[structopt(name = "intersect")]
+    Intersect(Combine),
+    /// Words of the first dictionary that are in none of the others
+    #[structopt(name = "subtract")]
+    Subtract(Combine),
+}
+
+#[derive(StructOpt, Debug)]
+struct Combine {
+    /// Write a packed dictionary instead of a word list, which is only read back from `.dict` files
+    #[structopt(long = "packed")]
+    packed: bool,
+    /// Output path, defaults to stdout
+    #[structopt(short = "o", long = "output", parse(from_os_str))]
+    output: Option<PathBuf>,
+    #[structopt(parse(from_os_str), required = true, min_values = 2)]
+    inputs: Vec<PathBuf>,
+}
+
+fn main() {
+    match Opt::from_args() {
+        Opt::Diff { old, new } => diff(&read_dict(&old), &read_dict(&new)),
+        Opt::Merge(combine) => combine.run(|a, b| a.union(b)),
+        Opt::Intersect(combine) => combine.run(|a, b| a.intersection(b)),
+        Opt::Subtract(combine) => combine.run(|a, b| a.difference(b)),
+    }
+}
+
+fn diff(old: &Dict, new: &Dict) {
+    let removed = old.difference(new);
+    let added = new.difference(old);
+    let mut changes: Vec<(&str, char)> = removed.words().map(|word| (word, '-'))
+        .chain(added.words().map(|word| (word, '+')))
+        .collect();
+
+    changes.sort();
+
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    for (word, change) in changes {
+        writeln!(stdout, "{}{}", change, word).expect("failed to write diff");
+    }
+}
+
+impl Combine {
+    fn run<F>(&self, op: F)
+    where
+        F: Fn(&Dict, &Dict) -> Dict,
+    {
+        let mut inputs = self.inputs.iter();
+        let first = inputs.next().expect("input dictionary missing");
+        let dict = inputs.fold(read_dict(first), |dict, path| op(&dict, &read_dict(path)));
+
+        let res = match self.output {
+            Some(ref path) => {
+                let file = File::create(path).expect(&path.display().to_string());
+                write_dict(&dict, BufWriter::new(file), self.packed)
+            },
+            None => {
+                let stdout = io::stdout();
+                let stdout = stdout.lock();
+                write_dict(&dict, stdout, self.packed)
+            },
+        };
+
+        res.expect("failed to write dictionary");
+    }
+}
+
+fn read_dict(path: &Path) -> Dict {
+    Dict::open(path).expect(&path.display().to_string())
+}
+
+fn write_dict<W: Write>(dict: &Dict, mut w: W, packed: bool) -> io::Result<()> {
+    if packed {
+        dict.serialize_packed(&mut w)?;
+    } else {
+        let mut words: Vec<&str> = dict.words().collect();
+        words.sort();
+
+        for word in words {
+            writeln!(w, "{}", word)?;
+        }
+    }
+
+    w.flush()
+}
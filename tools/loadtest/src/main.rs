@@ -0,0 +1,223 @@
+extern crate boggle_common;
+#[macro_use] extern crate failure;
+extern crate rand;
+extern crate structopt;
+
+use boggle_common::{client, server, PROTOCOL_VERSION};
+use boggle_common::client::message::ErrorCode;
+use boggle_common::connection::Connection;
+use failure::Error;
+use rand::{Rng, thread_rng};
+use rand::seq::SliceRandom;
+use std::collections::VecDeque;
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+
+/// Simulate many clients playing on a server and report how it copes.
+#[derive(StructOpt, Debug, Clone)]
+struct Opt {
+    /// Websocket url of the server
+    #[structopt(short = "s", long = "server", default_value = "ws://localhost:8001/ws")]
+    server: String,
+    /// Number of simulated clients
+    #[structopt(short = "c", long = "clients", default_value = "100")]
+    clients: usize,
+    /// How long every client plays, in seconds
+    #[structopt(short = "d", long = "duration", default_value = "30")]
+    duration: u64,
+    /// Word submissions per second and client
+    #[structopt(short = "r", long = "rate", default_value = "1")]
+    rate: f64,
+    /// Share of submissions that are not words of the grid
+    #[structopt(long = "invalid", default_value = "0.5")]
+    invalid: f64,
+    /// Delay between starting clients, in milliseconds
+    #[structopt(long = "ramp-up", default_value = "10")]
+    ramp_up: u64,
+    /// Prefix of the nicks of simulated clients
+    #[structopt(long = "nick-prefix", default_value = "load")]
+    nick_prefix: String,
+}
+
+/// Measurements of a single client.
+#[derive(Debug, Default)]
+struct Stats {
+    connected: usize,
+    sent: usize,
+    received: usize,
+    errors: Vec<String>,
+    /// Time from submitting a new word until the server reports it as found
+    latencies: Vec<Duration>,
+}
+
+impl Stats {
+    fn merge(&mut self, other: Stats) {
+        self.connected += other.connected;
+        self.sent += other.sent;
+        self.received += other.received;
+        self.errors.extend(other.errors);
+        self.latencies.extend(other.latencies);
+    }
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    let started = Instant::now();
+    let mut clients = Vec::new();
+
+    for i in 0..opt.clients {
+        let opt = opt.clone();
+        let nick = format!("{}{}-{}", opt.nick_prefix, process::id(), i);
+
+        clients.push(thread::spawn(move || {
+            let mut stats = Stats::default();
+
+            if let Err(e) = simulate(&opt, nick, &mut stats) {
+                stats.errors.push(e.to_string());
+            }
+
+            stats
+        }));
+
+        thread::sleep(Duration::from_millis(opt.ramp_up));
+    }
+
+    let mut stats = Stats::default();
+
+    for client in clients {
+        match client.join() {
+            Ok(client) => stats.merge(client),
+            Err(_) => stats.errors.push("client panicked".into()),
+        }
+    }
+
+    report(&opt, &mut stats, started.elapsed());
+}
+
+fn simulate(opt: &Opt, nick: String, stats: &mut Stats) -> Result<(), Error> {
+    let mut rng = thread_rng();
+    let mut connection = Connection::connect(&opt.server, Duration::from_millis(5))?;
+    stats.connected += 1;
+
+    send(&mut connection, stats, &server::Message::Hello(server::message::Hello {
+        protocol_version: PROTOCOL_VERSION,
+        capabilities: Vec::new(),
+    }))?;
+
+    send(&mut connection, stats, &server::Message::Login(server::message::Login {
+        nick: nick.clone(),
+    }))?;
+
+    let end = Instant::now() + Duration::from_secs(opt.duration);
+    let interval = Duration::from_secs_f64(1.0 / opt.rate.max(0.001));
+    let mut next_submission = Instant::now() + interval;
+    let mut unsubmitted: Vec<String> = Vec::new();
+    let mut pending: VecDeque<Instant> = VecDeque::new();
+    let mut found = 0;
+
+    while Instant::now() < end {
+        if let Some(msg) = recv(&mut connection, stats)? {
+            match msg {
+                client::Message::NewGame(new_game) => {
+                    unsubmitted = new_game.words.words().filter_map(|word| word.ok()).collect();
+                    unsubmitted.shuffle(&mut rng);
+                    pending.clear();
+                    found = 0;
+                },
+                client::Message::NickAlreadyInUse(_) => bail!("Nick '{}' is already in use", nick),
+                client::Message::Incompatible(msg) => bail!("{}", msg.message),
+                client::Message::Shutdown(msg) => bail!("{}", msg.message),
+                client::Message::Error { code, message } => if code.is_fatal() || code == ErrorCode::NickTaken {
+                    bail!("{}", message);
+                } else {
+                    stats.errors.push(message);
+                },
+                client::Message::PlayerStatus(client::message::PlayerStatus::FoundWords { nick: ref player, count }) if *player == nick => {
+                    while found < count {
+                        found += 1;
+
+                        if let Some(submitted) = pending.pop_front() {
+                            stats.latencies.push(submitted.elapsed());
+                        }
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        if Instant::now() < next_submission {
+            continue;
+        }
+
+        next_submission += interval;
+
+        let word = match unsubmitted.pop() {
+            Some(word) if !rng.gen_bool(opt.invalid.min(1.0).max(0.0)) => {
+                pending.push_back(Instant::now());
+                word
+            },
+            word => {
+                // Put back the valid word that was skipped
+                unsubmitted.extend(word);
+                (0..rng.gen_range(3, 9)).map(|_| rng.gen_range(b'a', b'z' + 1) as char).collect()
+            },
+        };
+
+        send(&mut connection, stats, &server::Message::SubmitWord(server::message::SubmitWord {
+            word,
+        }))?;
+    }
+
+    connection.close()?;
+
+    Ok(())
+}
+
+fn send(connection: &mut Connection, stats: &mut Stats, msg: &server::Message) -> Result<(), Error> {
+    connection.send(msg)?;
+    stats.sent += 1;
+    Ok(())
+}
+
+fn recv(connection: &mut Connection, stats: &mut Stats) -> Result<Option<client::Message>, Error> {
+    let msg = connection.try_recv()?;
+
+    if msg.is_some() {
+        stats.received += 1;
+    }
+
+    Ok(msg)
+}
+
+fn report(opt: &Opt, stats: &mut Stats, elapsed: Duration) {
+    let secs = elapsed.as_secs_f64();
+
+    println!("clients:   {} of {} connected", stats.connected, opt.clients);
+    println!("sent:      {} messages ({:.1}/s)", stats.sent, stats.sent as f64 / secs);
+    println!("received:  {} messages ({:.1}/s)", stats.received, stats.received as f64 / secs);
+    println!("errors:    {}", stats.errors.len());
+
+    stats.errors.sort();
+    stats.errors.dedup();
+
+    for error in &stats.errors {
+        println!("  {}", error);
+    }
+
+    stats.latencies.sort();
+
+    if stats.latencies.is_empty() {
+        println!("latency:   no found words were confirmed");
+        return;
+    }
+
+    let percentile = |p: usize| stats.latencies[(stats.latencies.len() - 1) * p / 100];
+
+    println!("latency:   {} samples", stats.latencies.len());
+
+    for &p in &[50, 90, 99, 100] {
+        println!("  p{:<3} {:?}", p, percentile(p));
+    }
+}
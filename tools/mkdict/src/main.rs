@@ -1,13 +1,16 @@
 extern crate boggle;
 
-use boggle::Dict;
+use boggle::{Dict, Definitions};
 use std::fs::File;
 use std::env::args;
 use std::io::{BufReader, BufRead};
+use std::path::Path;
 
 fn main() {
     let src_path = args().nth(1).expect("missing path to word list");
     let dst_path = args().nth(2).expect("missing path to output");
+    // Any further arguments are WordNet data files to take definitions from
+    let wordnet_paths: Vec<String> = args().skip(3).collect();
     let input = File::open(&src_path).expect(&src_path);
     let input = BufReader::new(input);
     let mut output = File::create(&dst_path).expect(&dst_path);
@@ -16,9 +19,26 @@ fn main() {
     for word in input.lines() {
         let word = word.expect("could not read word from word list");
         let word = word.trim();
-        
+
         dict.add(word);
     }
 
     dict.serialize_packed(&mut output).expect("failed to write dict");
+
+    if wordnet_paths.is_empty() {
+        return;
+    }
+
+    let mut definitions = Definitions::new();
+
+    for path in &wordnet_paths {
+        let input = File::open(path).expect(path);
+        definitions.extend_from_wordnet(BufReader::new(input)).expect("failed to read definitions");
+    }
+
+    definitions.retain(|word| dict.contains(word));
+
+    let defs_path = Path::new(&dst_path).with_extension("defs");
+    let mut output = File::create(&defs_path).expect(&defs_path.display().to_string());
+    definitions.serialize(&mut output).expect("failed to write definitions");
 }
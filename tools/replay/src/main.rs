@@ -0,0 +1,132 @@
+extern crate boggle;
+extern crate boggle_common;
+extern crate chrono;
+#[macro_use] extern crate failure;
+extern crate structopt;
+
+use boggle::Grid;
+use boggle_common::replay::{Event, Replay};
+use chrono::{DateTime, Utc};
+use failure::Error;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::process;
+use structopt::StructOpt;
+
+/// Inspect the round logs the server writes with `--replay-dir`.
+///
+/// Prints the timeline of every round. Scores are recomputed from the
+/// submissions and can be checked against what the server recorded.
+#[derive(StructOpt, Debug)]
+struct Opt {
+    /// Don't print the timeline
+    #[structopt(short = "q", long = "quiet")]
+    quiet: bool,
+    /// Print the scores recomputed from the submissions
+    #[structopt(long = "scores")]
+    scores: bool,
+    /// Check recorded verdicts and results against the recomputed ones,
+    /// exiting with an error on any mismatch
+    #[structopt(long = "verify")]
+    verify: bool,
+    #[structopt(parse(from_os_str), required = true)]
+    logs: Vec<PathBuf>,
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    let mut mismatches = 0;
+
+    for path in &opt.logs {
+        match replay(&opt, path) {
+            Ok(count) => mismatches += count,
+            Err(e) => {
+                eprintln!("{}: {}", path.display(), e);
+                process::exit(1);
+            },
+        }
+    }
+
+    if opt.verify && mismatches > 0 {
+        eprintln!("{} mismatches found", mismatches);
+        process::exit(1);
+    }
+}
+
+/// Replay a log, returning the number of mismatches.
+fn replay(opt: &Opt, path: &Path) -> Result<usize, Error> {
+    let events = Event::read_all(BufReader::new(File::open(path)?))?;
+    let start = match events.first() {
+        Some(Event::RoundStarted { time, .. }) => *time,
+        _ => bail!("log doesn't start with a round_started event"),
+    };
+
+    let mut replay = Replay::new();
+    let mut mismatches = Vec::new();
+    let mut ended = false;
+
+    if !opt.quiet {
+        println!("{}", path.display());
+    }
+
+    for event in &events {
+        if !opt.quiet {
+            println!("{:>8} {}", offset(start, event.time()), describe(event));
+        }
+
+        if let Event::RoundEnded { .. } = event {
+            ended = true;
+        }
+
+        mismatches.extend(replay.check(event));
+    }
+
+    if opt.scores {
+        println!("scores{}:", if ended { "" } else { " (round unfinished)" });
+
+        for result in replay.results() {
+            println!("{:>8} {} ({} words)", result.score, result.nick, result.words.len());
+        }
+    }
+
+    if opt.verify {
+        for mismatch in &mismatches {
+            println!("{}: {}", path.display(), mismatch);
+        }
+    }
+
+    Ok(mismatches.len())
+}
+
+fn describe(event: &Event) -> String {
+    match event {
+        Event::RoundStarted { grid, dictionary, deadline, words, .. } => format!(
+            "round started on {} with {} words of dictionary '{}', ends at {}",
+            rows(grid), words.len(), dictionary, deadline.to_rfc3339(),
+        ),
+        Event::Joined { nick, bot: false, .. } => format!("{} joined", nick),
+        Event::Joined { nick, bot: true, .. } => format!("{} joined (bot)", nick),
+        Event::Left { nick, .. } => format!("{} left", nick),
+        Event::Submitted { nick, word, verdict, .. } => format!("{} submitted '{}': {}", nick, word, verdict),
+        Event::RoundEnded { results, .. } => match results.first() {
+            Some(winner) => format!("round ended, {} won with {} points", winner.nick, winner.score),
+            None => "round ended without players".into(),
+        },
+    }
+}
+
+/// The grid as rows separated by slashes
+fn rows(grid: &Grid) -> String {
+    (0..Grid::HEIGHT)
+        .map(|y| (0..Grid::WIDTH).filter_map(|x| grid.get(x, y)).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Time since the start of the round, as `+m:ss`
+fn offset(start: DateTime<Utc>, time: DateTime<Utc>) -> String {
+    let seconds = time.signed_duration_since(start).num_seconds().max(0);
+
+    format!("+{}:{:02}", seconds / 60, seconds % 60)
+}
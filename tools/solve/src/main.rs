@@ -1,17 +1,246 @@
 extern crate boggle;
 extern crate dict;
+extern crate structopt;
+extern crate serde;
+#[macro_use] extern crate serde_derive;
+extern crate serde_json;
 
-use std::env::args;
-use boggle::Grid;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use boggle::{Dict, Grid};
+use structopt::StructOpt;
 
 use dict::DICT;
 
+/// Find all words on boards.
+///
+/// Boards are taken from the arguments, `--file` or stdin, in that order.
+/// A board is either one line of 16 letters or 4 lines of 4 letters,
+/// letters may be separated by spaces.
+#[derive(StructOpt, Debug)]
+struct Opt {
+    /// Boards to solve
+    boards: Vec<String>,
+    /// Read boards from this file
+    #[structopt(short = "f", long = "file", parse(from_os_str))]
+    file: Option<PathBuf>,
+    /// Dictionary to solve with, the built in one if omitted
+    #[structopt(long = "dict", parse(from_os_str))]
+    dict: Option<PathBuf>,
+    /// Sort words by `alpha`, `length` or `points`
+    #[structopt(short = "s", long = "sort", default_value = "alpha")]
+    sort: Sort,
+    /// Show the points of each word and the total
+    #[structopt(long = "scores")]
+    scores: bool,
+    /// Show the cells each word is made of
+    #[structopt(long = "paths")]
+    paths: bool,
+    /// Print JSON instead of text
+    #[structopt(long = "json")]
+    json: bool,
+    /// Only show words with at least this many letters
+    #[structopt(long = "min-length", default_value = "3")]
+    min_length: usize,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Sort {
+    Alpha,
+    Length,
+    Points,
+}
+
+impl std::str::FromStr for Sort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "alpha" => Ok(Sort::Alpha),
+            "length" => Ok(Sort::Length),
+            "points" => Ok(Sort::Points),
+            _ => Err(format!("unknown sort order '{}'", s)),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct Solution {
+    board: String,
+    words: Vec<Word>,
+    total: u32,
+}
+
+#[derive(Serialize, Debug)]
+struct Word {
+    word: String,
+    points: u32,
+    path: Vec<(usize, usize)>,
+}
+
 fn main() {
-    let grid_description = args().nth(1).expect("grid description missing");
-    let grid = grid_description.parse::<Grid>().unwrap();
-    let words = grid.words(&DICT);
+    let opt = Opt::from_args();
+
+    let custom_dict;
+    let dict: &Dict = match opt.dict {
+        Some(ref path) => {
+            custom_dict = Dict::open(path).expect(&path.display().to_string());
+            &custom_dict
+        },
+        None => &*DICT,
+    };
+
+    let boards = if !opt.boards.is_empty() {
+        opt.boards.clone()
+    } else {
+        let mut input = String::new();
+
+        let res = match opt.file {
+            Some(ref path) => File::open(path).and_then(|mut file| file.read_to_string(&mut input)),
+            None => io::stdin().read_to_string(&mut input),
+        };
+
+        res.expect("failed to read boards");
+
+        match parse_boards(&input) {
+            Ok(boards) => boards,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    };
+
+    let solutions: Vec<Solution> = boards.iter()
+        .map(|board| solve(board, dict, &opt))
+        .collect();
+
+    if opt.json {
+        serde_json::to_writer_pretty(io::stdout(), &solutions).expect("failed to write solutions");
+        println!();
+        return;
+    }
+
+    for solution in &solutions {
+        if solutions.len() > 1 {
+            println!("{}:", solution.board);
+        }
+
+        for word in &solution.words {
+            print_word(word, &opt);
+        }
+
+        if opt.scores {
+            println!("total: {}", solution.total);
+        }
+    }
+}
+
+/// Split input into boards, joining square layouts into single lines.
+///
+/// Malformed input is reported with its line number.
+fn parse_boards(input: &str) -> Result<Vec<String>, String> {
+    let mut boards = Vec::new();
+    // Rows of the square layout being read and the line it started on
+    let mut rows = String::new();
+    let mut first_row = 0;
+
+    for (number, line) in input.lines().enumerate().map(|(i, line)| (i + 1, line)) {
+        let line: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+        let len = line.chars().count();
 
-    for word in words {
-        println!("{}", word);
+        if let Some(ch) = line.chars().find(|c| !c.is_alphabetic()) {
+            return Err(format!("line {}: invalid character '{}'", number, ch));
+        }
+
+        if len == Grid::WIDTH {
+            if rows.is_empty() {
+                first_row = number;
+            }
+
+            rows.push_str(&line);
+
+            if rows.chars().count() == Grid::WIDTH * Grid::HEIGHT {
+                boards.push(rows.split_off(0));
+            }
+
+            continue;
+        }
+
+        if !rows.is_empty() {
+            return Err(incomplete(first_row, &rows));
+        }
+
+        match len {
+            0 => {},
+            len if len == Grid::WIDTH * Grid::HEIGHT => boards.push(line),
+            len => return Err(format!(
+                "line {}: expected {} or {} letters, got {}",
+                number, Grid::WIDTH * Grid::HEIGHT, Grid::WIDTH, len,
+            )),
+        }
+    }
+
+    if !rows.is_empty() {
+        return Err(incomplete(first_row, &rows));
     }
+
+    Ok(boards)
+}
+
+fn incomplete(first_row: usize, rows: &str) -> String {
+    format!("line {}: board has only {} of {} rows", first_row, rows.chars().count() / Grid::WIDTH, Grid::HEIGHT)
+}
+
+fn solve(board: &str, dict: &Dict, opt: &Opt) -> Solution {
+    let grid = match board.to_lowercase().parse::<Grid>() {
+        Ok(grid) => grid,
+        Err(e) => {
+            eprintln!("{}: {}", board, e);
+            std::process::exit(1);
+        }
+    };
+
+    let mut words: Vec<Word> = grid.words(dict)
+        .into_iter()
+        .filter(|word| word.chars().count() >= opt.min_length)
+        .map(|word| Word {
+            points: boggle::points(&word),
+            path: grid.path(&word).unwrap_or_default(),
+            word,
+        })
+        .collect();
+
+    match opt.sort {
+        Sort::Alpha => words.sort_by(|a, b| a.word.cmp(&b.word)),
+        Sort::Length => words.sort_by(|a, b| b.word.len().cmp(&a.word.len()).then_with(|| a.word.cmp(&b.word))),
+        Sort::Points => words.sort_by(|a, b| b.points.cmp(&a.points).then_with(|| a.word.cmp(&b.word))),
+    }
+
+    Solution {
+        board: board.to_owned(),
+        total: words.iter().map(|word| word.points).sum(),
+        words,
+    }
+}
+
+fn print_word(word: &Word, opt: &Opt) {
+    let mut line = String::new();
+
+    if opt.scores {
+        line.push_str(&format!("{:>2} ", word.points));
+    }
+
+    line.push_str(&word.word);
+
+    if opt.paths {
+        let path: Vec<String> = word.path.iter()
+            .map(|&(x, y)| format!("({},{})", x, y))
+            .collect();
+
+        line.push_str(&format!(" {}", path.join("->")));
+    }
+
+    println!("{}", line);
 }
@@ -0,0 +1,131 @@
+use boggle::{Grid, PracticeSession};
+use boggle_common::client::message::{NewGame, PlayerStatus};
+use chrono::{DateTime, Utc, Duration};
+use std::collections::HashSet;
+
+/// Everything that is shown on screen.
+pub struct Game {
+    pub nick: String,
+    pub grid: Option<Grid>,
+    pub words: HashSet<String>,
+    /// Sorted alphabetically
+    pub found_words: Vec<String>,
+    /// Sorted by found words, descending
+    pub players: Vec<(String, usize)>,
+    pub deadline: DateTime<Utc>,
+    pub input: String,
+    pub status: String,
+}
+
+impl Game {
+    pub fn new(nick: String) -> Self {
+        Self {
+            nick,
+            grid: None,
+            words: HashSet::new(),
+            found_words: Vec::new(),
+            players: Vec::new(),
+            deadline: Utc::now(),
+            input: String::new(),
+            status: "Waiting for the game to start…".into(),
+        }
+    }
+
+    pub fn time_left(&self) -> Duration {
+        let time_left = self.deadline.signed_duration_since(Utc::now());
+
+        if time_left < Duration::zero() {
+            return Duration::zero();
+        }
+
+        time_left
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.grid.is_some() && self.time_left() == Duration::zero()
+    }
+
+    /// Words of the grid that have not been found, sorted alphabetically.
+    pub fn missed_words(&self) -> Vec<&str> {
+        let mut missed: Vec<&str> = self.words.iter()
+            .filter(|word| self.found_words.binary_search(word).is_err())
+            .map(|word| &**word)
+            .collect();
+
+        missed.sort();
+        missed
+    }
+
+    /// Take the typed word and return it if it is a new word of the grid.
+    pub fn take_input(&mut self) -> Option<String> {
+        let word = self.input.trim().to_lowercase();
+        self.input.clear();
+
+        if word.is_empty() || self.is_over() {
+            return None;
+        }
+
+        if !self.words.contains(&word) {
+            self.status = format!("'{}' is not on the grid", word);
+            return None;
+        }
+
+        match self.found_words.binary_search(&word) {
+            Ok(_) => {
+                self.status = format!("'{}' was found already", word);
+                None
+            },
+            Err(index) => {
+                self.status = format!("Found '{}'", word);
+                self.found_words.insert(index, word.clone());
+                Some(word)
+            },
+        }
+    }
+
+    pub fn new_game(&mut self, new_game: NewGame) {
+        self.nick = new_game.nick;
+        self.grid = Some(new_game.grid);
+        self.words = new_game.words.words().filter_map(|word| word.ok()).collect();
+        self.found_words.clear();
+        self.deadline = new_game.deadline;
+        self.status = "New game started".into();
+
+        for (_, found_words) in &mut self.players {
+            *found_words = 0;
+        }
+    }
+
+    /// Show a new practice session.
+    pub fn practice(&mut self, session: &PracticeSession) {
+        let time_left = Duration::from_std(session.time_left()).unwrap_or_else(|_| Duration::zero());
+
+        self.grid = Some(*session.grid());
+        self.words = session.words().clone();
+        self.deadline = Utc::now() + time_left;
+        self.status = "Practice round started, Enter starts the next round once time is up".into();
+        self.practice_progress(session);
+    }
+
+    pub fn practice_progress(&mut self, session: &PracticeSession) {
+        self.found_words = session.found_words().to_vec();
+        self.players = vec![(self.nick.clone(), self.found_words.len())];
+    }
+
+    pub fn player_status(&mut self, status: PlayerStatus) {
+        match status {
+            PlayerStatus::FoundWords { nick, count } => {
+                if let Some((_, ref mut found_words)) = self.players.iter_mut().find(|(name, _)| &nick == name) {
+                    *found_words = count;
+                } else {
+                    self.players.push((nick, count));
+                }
+
+                self.players.sort_by(|(_, count_a), (_, count_b)| count_b.cmp(&count_a));
+            },
+            PlayerStatus::Disconnected { nick } => {
+                self.players.retain(|(name, _)| &nick != name);
+            },
+        }
+    }
+}
@@ -0,0 +1,163 @@
+extern crate boggle;
+extern crate boggle_common;
+extern crate dict;
+#[macro_use] extern crate failure;
+extern crate chrono;
+extern crate crossterm;
+extern crate structopt;
+
+use boggle::PracticeSession;
+use boggle_common::{client, server};
+use boggle_common::connection::Connection;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use dict::DICT;
+use failure::Error;
+use std::mem;
+use std::process;
+use std::time::Duration;
+use structopt::StructOpt;
+
+mod game;
+use game::Game;
+
+mod view;
+use view::Terminal;
+
+/// Play boggle in the terminal.
+#[derive(StructOpt, Debug)]
+struct Opt {
+    /// Websocket url of the server
+    #[structopt(short = "s", long = "server", default_value = "ws://localhost:8001/ws")]
+    server: String,
+    /// Play alone against the clock, without a server
+    #[structopt(long = "practice")]
+    practice: bool,
+    /// Length of a practice round in seconds
+    #[structopt(long = "duration", default_value = "180")]
+    duration: u64,
+    /// Nick to play as, required when playing online
+    nick: Option<String>,
+}
+
+enum Action {
+    Quit,
+    Submit,
+    None,
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    let res = match opt.practice {
+        true => run_practice(&opt),
+        false => run_online(&opt),
+    };
+
+    if let Err(e) = res {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn run_online(opt: &Opt) -> Result<(), Error> {
+    let nick = opt.nick.clone().ok_or_else(|| format_err!("A nick is required to play online"))?;
+    // Reading must not block the input handling for long
+    let mut connection = Connection::connect(&opt.server, Duration::from_millis(10))?;
+
+    connection.handshake()?;
+    connection.send(&server::Message::Login(server::message::Login {
+        nick: nick.clone(),
+    }))?;
+
+    let mut terminal = Terminal::enter()?;
+    let mut game = Game::new(nick);
+
+    loop {
+        terminal.render(&game)?;
+
+        match poll_input(&mut game)? {
+            Action::Quit => return Ok(()),
+            Action::Submit => if let Some(word) = game.take_input() {
+                connection.send(&server::Message::SubmitWord(server::message::SubmitWord {
+                    word,
+                }))?;
+            },
+            Action::None => {},
+        }
+
+        while let Some(msg) = connection.try_recv()? {
+            match msg {
+                client::Message::NewGame(new_game) => game.new_game(new_game),
+                client::Message::NickAlreadyInUse(msg) => bail!("'{}' is already in use", msg.nick),
+                client::Message::PlayerStatus(status) => game.player_status(status),
+                client::Message::Incompatible(msg) => bail!("{}", msg.message),
+                client::Message::Shutdown(msg) => bail!("{}", msg.message),
+                // Without a game there is nothing to continue with
+                client::Message::Error { code, message } => if code.is_fatal() || game.grid.is_none() {
+                    bail!("{}", message);
+                } else {
+                    game.status = message;
+                },
+                client::Message::Chat(chat) => game.status = format!("<{}> {}", chat.nick, chat.text),
+                client::Message::Dictionaries(_)
+                | client::Message::Definition(_)
+                | client::Message::RoundResults(_)
+                | client::Message::Session(_)
+                | client::Message::Welcome(_) => {},
+            }
+        }
+    }
+}
+
+fn run_practice(opt: &Opt) -> Result<(), Error> {
+    let duration = Duration::from_secs(opt.duration);
+    let nick = opt.nick.clone().unwrap_or_else(|| "you".into());
+    let mut terminal = Terminal::enter()?;
+    let mut game = Game::new(nick);
+    let mut session = PracticeSession::random(&DICT, duration);
+
+    game.practice(&session);
+
+    loop {
+        terminal.render(&game)?;
+
+        match poll_input(&mut game)? {
+            Action::Quit => return Ok(()),
+            // Enter starts the next round once time is up
+            Action::Submit if session.is_over() => {
+                game.input.clear();
+                session = PracticeSession::random(&DICT, duration);
+                game.practice(&session);
+            },
+            Action::Submit => {
+                let word = mem::replace(&mut game.input, String::new());
+
+                game.status = match session.submit(&word) {
+                    Ok(points) => format!("'{}' is worth {} points, {} in total", word.trim(), points, session.score()),
+                    Err(e) => e.to_string(),
+                };
+
+                game.practice_progress(&session);
+            },
+            Action::None => {},
+        }
+    }
+}
+
+fn poll_input(game: &mut Game) -> Result<Action, Error> {
+    if !event::poll(Duration::from_millis(100))? {
+        return Ok(Action::None);
+    }
+
+    if let Event::Key(key) = event::read()? {
+        match key {
+            KeyEvent { code: KeyCode::Esc, .. } => return Ok(Action::Quit),
+            KeyEvent { code: KeyCode::Char('c'), modifiers } if modifiers.contains(KeyModifiers::CONTROL) => return Ok(Action::Quit),
+            KeyEvent { code: KeyCode::Char(ch), .. } if ch.is_alphabetic() => game.input.push(ch),
+            KeyEvent { code: KeyCode::Backspace, .. } => { game.input.pop(); },
+            KeyEvent { code: KeyCode::Enter, .. } => return Ok(Action::Submit),
+            _ => {},
+        }
+    }
+
+    Ok(Action::None)
+}
@@ -0,0 +1,122 @@
+use boggle::Grid;
+use crossterm::{self, cursor, queue, execute, terminal};
+use crossterm::style::{Print, Attribute, SetAttribute};
+use std::io::{self, Write};
+use game::Game;
+
+/// Raw mode on the alternate screen, restored on drop.
+pub struct Terminal {
+    stdout: io::Stdout,
+}
+
+impl Terminal {
+    pub fn enter() -> crossterm::Result<Self> {
+        let mut stdout = io::stdout();
+
+        terminal::enable_raw_mode()?;
+        execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+        Ok(Self {
+            stdout,
+        })
+    }
+
+    pub fn render(&mut self, game: &Game) -> crossterm::Result<()> {
+        let (width, _) = terminal::size()?;
+        let out = &mut self.stdout;
+        let mut row = 0;
+
+        queue!(out, terminal::Clear(terminal::ClearType::All))?;
+
+        let time_left = game.time_left();
+        line(out, &mut row, &format!(
+            "boggle — {}    {}:{:02}",
+            game.nick,
+            time_left.num_minutes(),
+            time_left.num_seconds() % 60,
+        ))?;
+        row += 1;
+
+        // Grid on the left, players on the right
+        for y in 0..Grid::HEIGHT.max(game.players.len()) {
+            let mut text = String::new();
+
+            for x in 0..Grid::WIDTH {
+                let ch = game.grid.and_then(|grid| grid.get(x, y)).unwrap_or(' ');
+                text.push_str(&format!(" {}", ch.to_ascii_uppercase()));
+            }
+
+            text.push_str("     ");
+
+            if let Some((nick, count)) = game.players.get(y) {
+                let marker = if *nick == game.nick { "*" } else { " " };
+                text.push_str(&format!("{}({}) {}", marker, count, nick));
+            }
+
+            line(out, &mut row, text.trim_end())?;
+        }
+
+        row += 1;
+        queue!(out, cursor::MoveTo(0, row), SetAttribute(Attribute::Bold), Print(format!("> {}_", game.input)), SetAttribute(Attribute::Reset))?;
+        row += 1;
+        line(out, &mut row, &game.status)?;
+        row += 1;
+
+        line(out, &mut row, &format!("Found {} out of {} words:", game.found_words.len(), game.words.len()))?;
+        wrapped(out, &mut row, width, game.found_words.iter().map(|word| &**word))?;
+
+        if game.is_over() {
+            row += 1;
+            line(out, &mut row, "Round over, missed words:")?;
+            wrapped(out, &mut row, width, game.missed_words().into_iter())?;
+        }
+
+        row += 1;
+        line(out, &mut row, "Enter: submit word  Esc: quit")?;
+
+        out.flush()?;
+
+        Ok(())
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = execute!(self.stdout, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn line<W: Write>(out: &mut W, row: &mut u16, text: &str) -> crossterm::Result<()> {
+    queue!(out, cursor::MoveTo(0, *row), Print(text))?;
+    *row += 1;
+    Ok(())
+}
+
+/// Print words separated by spaces, wrapping them at `width`.
+fn wrapped<'a, W, I>(out: &mut W, row: &mut u16, width: u16, words: I) -> crossterm::Result<()>
+where
+    W: Write,
+    I: Iterator<Item = &'a str>,
+{
+    let mut text = String::new();
+
+    for word in words {
+        if !text.is_empty() && text.len() + word.len() + 1 > width as usize {
+            line(out, row, &text)?;
+            text.clear();
+        }
+
+        if !text.is_empty() {
+            text.push(' ');
+        }
+
+        text.push_str(word);
+    }
+
+    if !text.is_empty() {
+        line(out, row, &text)?;
+    }
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use yew::prelude::*;
 use yew::services::ConsoleService;
 use yew::services::interval::{IntervalService, IntervalTask};
+use yew::services::storage::{Area, StorageService};
 use yew::services::websocket::{WebSocketService, WebSocketStatus, WebSocketTask};
 use boggle::Grid;
 use boggle_common::{client, server};
@@ -10,6 +11,15 @@ use failure::Error;
 use BinaryMessage;
 use chrono::{DateTime, Utc, Duration};
 
+/// Number of chat messages that are kept
+const MAX_CHAT_HISTORY: usize = 50;
+
+/// Session storage key of the token that allows resuming the game
+const SESSION_KEY: &str = "boggle.session";
+
+/// Seconds to wait before reconnecting
+const RECONNECT_DELAY: i64 = 3;
+
 pub mod login;
 pub use self::login::Login;
 
@@ -17,10 +27,18 @@ pub mod play;
 pub use self::play::Play;
 
 pub struct Model {
+    link: ComponentLink<Self>,
     server: WebSocketTask,
     console: ConsoleService,
+    storage: StorageService,
+    /// Token of the current game, to resume it after losing the connection
+    session: Option<String>,
+    /// When to try reconnecting to resume the session
+    reconnect_at: Option<DateTime<Utc>>,
     state: State,
     game: Game,
+    /// Last error reported by the server, shown until dismissed
+    error: Option<String>,
     _interval: IntervalTask,
 }
 
@@ -32,6 +50,16 @@ pub struct Game {
     found_words: Vec<String>,
     players: Vec<(String, usize)>,
     deadline: DateTime<Utc>,
+    dictionary: String,
+    dictionaries: Vec<client::message::DictionaryInfo>,
+    /// Dictionary of the next game
+    next_dictionary: String,
+    /// Last looked up word and its definition, if one is known
+    definition: Option<(String, Option<String>)>,
+    /// Recent chat messages, oldest first
+    chat: Vec<client::message::Chat>,
+    /// Results of the previous round, revealed to spectators
+    results: Option<client::message::RoundResults>,
 }
 
 impl Game {
@@ -49,6 +77,18 @@ impl Game {
             <>{ format!("{}:{:02}", m, s) }</>
         }
     }
+
+    /// Spectators watch without a nick
+    fn is_spectating(&self) -> bool {
+        self.nick.is_empty()
+    }
+
+    fn dictionary_description(&self) -> &str {
+        self.dictionaries.iter()
+            .find(|dict| dict.id == self.dictionary)
+            .map(|dict| &*dict.description)
+            .unwrap_or(&self.dictionary)
+    }
 }
 
 impl Default for Game {
@@ -60,6 +100,12 @@ impl Default for Game {
             found_words: <_>::default(),
             players: <_>::default(),
             deadline: now(),
+            dictionary: <_>::default(),
+            dictionaries: <_>::default(),
+            next_dictionary: <_>::default(),
+            definition: <_>::default(),
+            chat: <_>::default(),
+            results: <_>::default(),
         }
     }
 }
@@ -71,7 +117,14 @@ pub enum State {
 
 pub enum Msg {
     StartPlay(String),
+    StartSpectating,
     FoundWord(usize, String),
+    Define(String),
+    Chat(String),
+    SelectDictionary(String),
+    DismissError,
+    Connected,
+    Disconnected,
     ClientMessage(client::Message),
     RefreshUi,
 }
@@ -82,16 +135,23 @@ impl Component for Model {
 
     fn create(_props: (), link: ComponentLink<Self>) -> Self {
         let server = connect_to_server(&link).unwrap();
+        let mut storage = StorageService::new(Area::Session);
+        let session: Result<String, Error> = storage.restore(SESSION_KEY);
         let one_second = Duration::seconds(1).to_std().unwrap();
         let interval = IntervalService::new().spawn(one_second, link.send_back(|()| {
             Msg::RefreshUi
         }));
 
         Model {
+            link,
             server,
             state: State::Login,
             console: ConsoleService::new(),
+            storage,
+            session: session.ok(),
+            reconnect_at: None,
             game: <_>::default(),
+            error: None,
             _interval: interval,
         }
     }
@@ -99,9 +159,18 @@ impl Component for Model {
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             Msg::StartPlay(nick) => {
+                self.error = None;
+                self.hello();
                 self.server.send_binary(BinaryMessage(server::Message::Login(server::message::Login {
                     nick: nick,
                 }).to_vec()));
+                self.server.send_binary(BinaryMessage(server::Message::ListDictionaries.to_vec()));
+            },
+            Msg::StartSpectating => {
+                self.error = None;
+                self.hello();
+                self.server.send_binary(BinaryMessage(server::Message::Spectate.to_vec()));
+                self.server.send_binary(BinaryMessage(server::Message::ListDictionaries.to_vec()));
             },
             Msg::FoundWord(index, word) => {
                 self.console.log(&format!("Found: {}", word));
@@ -110,20 +179,110 @@ impl Component for Model {
                     word,
                 }).to_vec()));
             },
-            Msg::RefreshUi => {},
+            Msg::Define(word) => {
+                self.server.send_binary(BinaryMessage(server::Message::Define(server::message::Define {
+                    word,
+                }).to_vec()));
+            },
+            Msg::Chat(text) => {
+                self.server.send_binary(BinaryMessage(server::Message::Chat(server::message::Chat {
+                    text,
+                }).to_vec()));
+            },
+            Msg::SelectDictionary(id) => {
+                self.server.send_binary(BinaryMessage(server::Message::SelectDictionary(server::message::SelectDictionary {
+                    id,
+                }).to_vec()));
+            },
+            Msg::DismissError => self.error = None,
+            Msg::Connected => {
+                self.console.info("ws: opened");
+
+                // Continue the game of a previous connection, e.g. after a restart
+                if let Some(token) = self.session.clone() {
+                    self.hello();
+                    self.server.send_binary(BinaryMessage(server::Message::Resume(server::message::Resume {
+                        token,
+                    }).to_vec()));
+                    self.server.send_binary(BinaryMessage(server::Message::ListDictionaries.to_vec()));
+                }
+            },
+            Msg::Disconnected => {
+                self.console.error("ws: closed");
+
+                if self.session.is_some() && self.reconnect_at.is_none() {
+                    self.reconnect_at = Some(now() + Duration::seconds(RECONNECT_DELAY));
+                }
+            },
+            Msg::RefreshUi => {
+                if self.reconnect_at.map_or(false, |at| now() >= at) {
+                    self.reconnect_at = None;
+
+                    match connect_to_server(&self.link) {
+                        Ok(server) => self.server = server,
+                        Err(e) => self.console.error(&format!("Failed to reconnect: {}", e)),
+                    }
+                }
+            },
             Msg::ClientMessage(client::Message::NewGame(new_game)) => {
                 self.game.nick = new_game.nick;
                 self.game.grid = new_game.grid;
-                self.game.words = new_game.words.values().cloned().collect();
+                self.game.words = new_game.words.words().filter_map(|word| word.ok()).collect();
                 self.game.found_words = Vec::new();
+                self.game.definition = None;
                 self.game.deadline = new_game.deadline;
+                self.game.dictionary = new_game.dictionary;
                 for (_, found_words) in &mut self.game.players {
                     *found_words = 0;
                 }
                 self.state = State::Play;
             },
             Msg::ClientMessage(client::Message::NickAlreadyInUse(msg)) => {
-                web::alert(&format!("'{}' is already in use", msg.nick));
+                self.error = Some(format!("'{}' is already in use", msg.nick));
+            },
+            Msg::ClientMessage(client::Message::Dictionaries(dictionaries)) => {
+                self.game.dictionaries = dictionaries.dictionaries;
+                self.game.next_dictionary = dictionaries.selected;
+            },
+            Msg::ClientMessage(client::Message::Welcome(welcome)) => {
+                self.console.log(&format!("Server speaks protocol version {}", welcome.protocol_version));
+            },
+            Msg::ClientMessage(client::Message::Incompatible(msg)) => {
+                self.error = Some(msg.message);
+            },
+            Msg::ClientMessage(client::Message::Error { code, message }) => {
+                self.console.error(&format!("server error {:?}: {}", code, message));
+
+                if code == client::message::ErrorCode::UnknownSession {
+                    // The game ended while disconnected, start over
+                    self.storage.remove(SESSION_KEY);
+                    self.session = None;
+                    self.state = State::Login;
+                } else {
+                    self.error = Some(message);
+                }
+            },
+            Msg::ClientMessage(client::Message::Chat(chat)) => {
+                if self.game.chat.len() >= MAX_CHAT_HISTORY {
+                    self.game.chat.remove(0);
+                }
+
+                self.game.chat.push(chat);
+            },
+            Msg::ClientMessage(client::Message::RoundResults(results)) => {
+                self.game.results = Some(results);
+            },
+            Msg::ClientMessage(client::Message::Shutdown(shutdown)) => {
+                self.error = Some(shutdown.message);
+            },
+            Msg::ClientMessage(client::Message::Session(session)) => {
+                self.storage.store(SESSION_KEY, Ok(session.token.clone()));
+                self.session = Some(session.token);
+                self.game.found_words = session.words;
+                self.error = None;
+            },
+            Msg::ClientMessage(client::Message::Definition(definition)) => {
+                self.game.definition = Some((definition.word, definition.definition));
             },
             Msg::ClientMessage(client::Message::PlayerStatus(client::message::PlayerStatus::FoundWords { nick, count })) => {
                 self.console.log(&format!("status: {} found {} words", nick, count));
@@ -146,31 +305,69 @@ impl Component for Model {
     }
 }
 
-impl Renderable<Self> for Model {
-    fn view(&self) -> Html<Self> {
+impl Model {
+    /// Start the handshake, which has to be the first message.
+    fn hello(&mut self) {
+        self.server.send_binary(BinaryMessage(server::Message::Hello(server::message::Hello {
+            protocol_version: boggle_common::PROTOCOL_VERSION,
+            capabilities: vec![
+                boggle_common::capability::DICTIONARIES.into(),
+                boggle_common::capability::DEFINITIONS.into(),
+                boggle_common::capability::CHAT.into(),
+                boggle_common::capability::RESUME.into(),
+            ],
+        }).to_vec()));
+    }
+
+    fn error(&self) -> Html<Self> {
+        match self.error {
+            Some(ref error) => html! {
+                <p class = "error", onclick = |_| Msg::DismissError,>{ error }</p>
+            },
+            None => html! {
+                <></>
+            },
+        }
+    }
+
+    fn state(&self) -> Html<Self> {
         match self.state {
             State::Login => html! {
                 <Login:
                     onlogin = |nick| Msg::StartPlay(nick),
+                    onspectate = |()| Msg::StartSpectating,
                 />
             },
             State::Play => html! {
                 <Play:
                     game = self.game.clone(),
                     on_found_word = |(index, word)| Msg::FoundWord(index, word),
+                    on_define = |word| Msg::Define(word),
+                    on_chat = |text| Msg::Chat(text),
+                    on_select_dictionary = |id| Msg::SelectDictionary(id),
                 />
             },
         }
     }
 }
 
+impl Renderable<Self> for Model {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <>
+                { self.error() }
+                { self.state() }
+            </>
+        }
+    }
+}
+
 fn connect_to_server(link: &ComponentLink<Model>) -> Result<WebSocketTask, Error> {
     let location = web::window().location().expect("window location");
-    let hostname = location.hostname()?;
-    let port = location.port()?;
-    let port = port.parse::<u16>()?;
-    let port = port + 1;
-    let url = format!("ws://{}:{}", hostname, port);
+    // The server serves the page and the websocket on the same host and port
+    let scheme = if location.protocol()? == "https:" { "wss" } else { "ws" };
+    let host = location.host()?;
+    let url = format!("{}://{}/ws", scheme, host);
 
     ConsoleService::new().log(&format!("Connecting to '{}'", url));
     let server = WebSocketService::new().connect(
@@ -181,13 +378,9 @@ fn connect_to_server(link: &ComponentLink<Model>) -> Result<WebSocketTask, Error
 
             Msg::ClientMessage(msg)
         }),
-        Callback::from(|status| {
-            let mut console = ConsoleService::new();
-            match status {
-                WebSocketStatus::Opened => console.info("ws: opened"),
-                WebSocketStatus::Closed => console.error("ws: closed"),
-                WebSocketStatus::Error => console.error("ws: error"),
-            }
+        link.send_back(|status| match status {
+            WebSocketStatus::Opened => Msg::Connected,
+            WebSocketStatus::Closed | WebSocketStatus::Error => Msg::Disconnected,
         }),
     );
 
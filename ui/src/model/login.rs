@@ -4,17 +4,20 @@ use yew::prelude::*;
 pub struct Login {
     nick: String,
     onlogin: Option<Callback<String>>,
+    onspectate: Option<Callback<()>>,
 }
 
 pub enum Msg {
     SetNick(String),
     DoLogin,
+    DoSpectate,
     NoOp,
 }
 
 #[derive(PartialEq, Clone, Default)]
 pub struct Props {
     pub onlogin: Option<Callback<String>>,
+    pub onspectate: Option<Callback<()>>,
 }
 
 impl Component for Login {
@@ -25,6 +28,7 @@ impl Component for Login {
         Self {
             nick: String::new(),
             onlogin: props.onlogin,
+            onspectate: props.onspectate,
         }
     }
 
@@ -34,6 +38,9 @@ impl Component for Login {
             Msg::DoLogin => if let Some(ref onlogin) = self.onlogin {
                 onlogin.emit(self.nick.clone());
             },
+            Msg::DoSpectate => if let Some(ref onspectate) = self.onspectate {
+                onspectate.emit(());
+            },
             Msg::NoOp => {},
         }
 
@@ -42,6 +49,7 @@ impl Component for Login {
 
     fn change(&mut self, props: Self::Properties) -> ShouldRender {
         self.onlogin = props.onlogin;
+        self.onspectate = props.onspectate;
 
         true
     }
@@ -58,6 +66,7 @@ impl Renderable<Self> for Login {
                     _ => Msg::NoOp,
                 },
             />
+            <button onclick = |_| Msg::DoSpectate,>{ "Watch without playing" }</button>
         }
     }
 }
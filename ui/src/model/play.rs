@@ -1,11 +1,16 @@
 use yew::prelude::*;
+use yew::html::ChangeData;
 use boggle::Grid;
 use super::Game;
 
 pub struct Play {
     game: Game,
     word: String,
+    chat_text: String,
     on_found_word: Option<Callback<(usize, String)>>,
+    on_define: Option<Callback<String>>,
+    on_chat: Option<Callback<String>>,
+    on_select_dictionary: Option<Callback<String>>,
 }
 
 impl Play {
@@ -28,17 +33,147 @@ impl Play {
             </table>
         }
     }
+
+    fn found_word(&self, word: &str) -> Html<Self> {
+        let define = word.to_owned();
+
+        html! {
+            <li onclick = move |_| Msg::Define(define.clone()),>{ word }</li>
+        }
+    }
+
+    fn definition(&self) -> Html<Self> {
+        match self.game.definition {
+            Some((ref word, Some(ref definition))) => html! {
+                <p class = "definition",><strong>{ word }</strong>{ format!(": {}", definition) }</p>
+            },
+            Some((ref word, None)) => html! {
+                <p class = "definition",>{ format!("No definition known for '{}'", word) }</p>
+            },
+            None => html! {
+                <></>
+            },
+        }
+    }
+
+    /// Lets players pick the dictionary of the next game.
+    fn dictionary_select(&self) -> Html<Self> {
+        if self.game.is_spectating() || self.game.dictionaries.is_empty() {
+            return html! {
+                <></>
+            };
+        }
+
+        html! {
+            <p>
+                { "Next game: " }
+                <select
+                    onchange = |e| match e {
+                        ChangeData::Select(select) => select.value().map_or(Msg::NoOp, Msg::SelectDictionary),
+                        _ => Msg::NoOp,
+                    },
+                >
+                    { for self.game.dictionaries.iter().map(|dict|
+                        match dict.id == self.game.next_dictionary {
+                            true => html! { <option value = &dict.id, selected = "selected",>{ &dict.description }</option> },
+                            false => html! { <option value = &dict.id,>{ &dict.description }</option> },
+                        }
+                    ) }
+                </select>
+            </p>
+        }
+    }
+
+    fn word_input(&self) -> Html<Self> {
+        if self.game.is_spectating() {
+            return html! {
+                <p>{ "Watching" }</p>
+            };
+        }
+
+        html! {
+            <input
+                value = &self.word,
+                oninput = |e| Msg::ChangeWord(e.value),
+                onkeydown = |e| match e.key().as_str() {
+                    "Enter" => Msg::SubmitWord,
+                    _ => Msg::NoOp,
+                },
+            />
+        }
+    }
+
+    fn results(&self) -> Html<Self> {
+        let results = match self.game.results {
+            Some(ref results) => results,
+            None => return html! {
+                <></>
+            },
+        };
+
+        html! {
+            <div class = "results",>
+                <p>{ "Last round:" }</p>
+                <ol>
+                    { for results.players.iter().map(|player| html! {
+                        <li>
+                            <strong>{ format!("({}) {}", player.score, player.nick) }</strong>
+                            { format!(": {}", player.words.join(", ")) }
+                        </li>
+                    }) }
+                </ol>
+            </div>
+        }
+    }
+
+    fn chat(&self) -> Html<Self> {
+        let input = if self.game.is_spectating() {
+            html! {
+                <></>
+            }
+        } else {
+            html! {
+                <input
+                    placeholder = "Chat",
+                    value = &self.chat_text,
+                    oninput = |e| Msg::ChangeChatText(e.value),
+                    onkeydown = |e| match e.key().as_str() {
+                        "Enter" => Msg::SendChat,
+                        _ => Msg::NoOp,
+                    },
+                />
+            }
+        };
+
+        html! {
+            <div class = "chat",>
+                <ul>
+                    { for self.game.chat.iter().map(|chat| html! {
+                        <li><strong>{ &chat.nick }</strong>{ format!(": {}", chat.text) }</li>
+                    }) }
+                </ul>
+                { input }
+            </div>
+        }
+    }
 }
 
 #[derive(PartialEq, Clone, Default)]
 pub struct Props {
     pub game: Game,
     pub on_found_word: Option<Callback<(usize, String)>>,
+    pub on_define: Option<Callback<String>>,
+    pub on_chat: Option<Callback<String>>,
+    pub on_select_dictionary: Option<Callback<String>>,
 }
 
 pub enum Msg {
     ChangeWord(String),
     SubmitWord,
+    Define(String),
+    ChangeChatText(String),
+    SendChat,
+    SelectDictionary(String),
     NoOp,
 }
  
@@ -50,7 +185,11 @@ impl Component for Play {
         Self {
             game: props.game,
             word: String::new(),
+            chat_text: String::new(),
             on_found_word: props.on_found_word,
+            on_define: props.on_define,
+            on_chat: props.on_chat,
+            on_select_dictionary: props.on_select_dictionary,
         }
     }
  
@@ -73,6 +212,24 @@ impl Component for Play {
 
                 self.word.clear();
             },
+            Msg::Define(word) => if let Some(ref on_define) = self.on_define {
+                on_define.emit(word);
+            },
+            Msg::ChangeChatText(text) => self.chat_text = text,
+            Msg::SendChat => {
+                let text = self.chat_text.trim().to_owned();
+
+                if !text.is_empty() {
+                    if let Some(ref on_chat) = self.on_chat {
+                        on_chat.emit(text);
+                    }
+                }
+
+                self.chat_text.clear();
+            },
+            Msg::SelectDictionary(id) => if let Some(ref on_select_dictionary) = self.on_select_dictionary {
+                on_select_dictionary.emit(id);
+            },
             Msg::NoOp => {},
         }
 
@@ -82,6 +239,9 @@ impl Component for Play {
     fn change(&mut self, props: Self::Properties) -> ShouldRender {
         self.game = props.game;
         self.on_found_word = props.on_found_word;
+        self.on_define = props.on_define;
+        self.on_chat = props.on_chat;
+        self.on_select_dictionary = props.on_select_dictionary;
 
         true
     }
@@ -93,14 +253,7 @@ impl Renderable<Play> for Play {
             <div class="play",>
                 <div>
                     { self.grid() }
-                    <input
-                        value = &self.word,
-                        oninput = |e| Msg::ChangeWord(e.value),
-                        onkeydown = |e| match e.key().as_str() {
-                            "Enter" => Msg::SubmitWord,
-                            _ => Msg::NoOp,
-                        },
-                    />
+                    { self.word_input() }
                     <ol class="playerlist",>
                         { for self.game.players.iter().map(|(nick, score)|
                             match &self.game.nick == nick {
@@ -112,15 +265,18 @@ impl Renderable<Play> for Play {
                 </div>
                 <div>
                     <p>{ self.game.time_left() }</p>
+                    <p>{ format!("Dictionary: {}", self.game.dictionary_description()) }</p>
+                    { self.dictionary_select() }
                     <p>
                         { format!("Found {} out of {} words:", self.game.found_words.len(), self.game.words.len()) }
                     </p>
                     <ul class = "wordlist",>
-                        { for self.game.found_words.iter().map(|word| html! {
-                            <li>{ word }</li>
-                        }) }
+                        { for self.game.found_words.iter().map(|word| self.found_word(word)) }
                     </ul>
+                    { self.definition() }
+                    { self.results() }
                 </div>
+                { self.chat() }
             </div>
         }
     }